@@ -0,0 +1,273 @@
+use num_bigint::{BigInt, BigUint};
+use num_rational::BigRational;
+use num_traits::{One, Signed, Zero};
+use rand::{RngCore, SeedableRng};
+
+use crate::{deck::Deck, error::DeckError, permutation::Permutation};
+
+/// An estimate of how well an operation randomizes a deck. Entry (i, j) counts how many times card i ended
+/// at position j when the operation was applied to a deck in the order 0..n. For a perfect shuffle every
+/// entry has probability 1/n.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionMatrix {
+    n: usize,
+    trials: usize,
+    counts: Vec<u64>,
+}
+
+impl PositionMatrix {
+    /// Apply the operation to a fresh deck of the cards 0..n for each trial, with all trials drawing from the
+    /// given RNG, and count where each card ends up. Panics if the operation does not leave the deck holding
+    /// exactly the cards 0..n.
+    pub fn estimate<R, F>(n: usize, trials: usize, rng: R, mut op: F) -> PositionMatrix
+    where
+        R: RngCore + SeedableRng,
+        F: FnMut(&mut Deck<usize, R>),
+    {
+        let mut counts = vec![0; n * n];
+        let mut deck = Deck::with_rng(Vec::with_capacity(n), rng);
+        for _ in 0..trials {
+            deck.cards.clear();
+            deck.cards.extend(0..n);
+            op(&mut deck);
+            assert_eq!(deck.len(), n, "the operation changed the number of cards");
+            for (pos, &card) in deck.iter().enumerate() {
+                counts[card * n + pos] += 1;
+            }
+        }
+        PositionMatrix { n, trials, counts }
+    }
+
+    /// Count where each card ended in a collection of observed permutations, such as those made by
+    /// Deck::record(). Returns an error if the permutations are not all the same length.
+    pub fn from_permutations(observed: &[Permutation]) -> Result<PositionMatrix, DeckError> {
+        let n = observed.first().map(|p| p.len()).unwrap_or(0);
+        let mut counts = vec![0; n * n];
+        for perm in observed {
+            if perm.len() != n {
+                return Err(DeckError::LengthMismatch {
+                    expected: n,
+                    len: perm.len(),
+                });
+            }
+            for (pos, &card) in perm.as_slice().iter().enumerate() {
+                counts[card * n + pos] += 1;
+            }
+        }
+        Ok(PositionMatrix {
+            n,
+            trials: observed.len(),
+            counts,
+        })
+    }
+
+    /// Number of cards in the deck.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Number of times the operation was applied.
+    pub fn trials(&self) -> usize {
+        self.trials
+    }
+
+    /// Number of trials in which card ended at position.
+    pub fn count(&self, card: usize, position: usize) -> u64 {
+        self.counts[card * self.n + position]
+    }
+
+    /// Estimated probability that card ends at position.
+    pub fn probability(&self, card: usize, position: usize) -> f64 {
+        self.count(card, position) as f64 / self.trials as f64
+    }
+
+    /// The full matrix of estimated probabilities, indexed by card then position.
+    pub fn probabilities(&self) -> Vec<Vec<f64>> {
+        (0..self.n)
+            .map(|card| (0..self.n).map(|pos| self.probability(card, pos)).collect())
+            .collect()
+    }
+
+    /// The largest absolute difference between an estimated probability and the uniform 1/n.
+    pub fn max_deviation(&self) -> f64 {
+        let uniform = 1.0 / self.n as f64;
+        self.counts
+            .iter()
+            .map(|&c| (c as f64 / self.trials as f64 - uniform).abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// Pearson's chi-square statistic for the whole matrix against the uniform expectation of trials/n in every
+    /// entry. Compare against degrees_of_freedom().
+    pub fn chi_square(&self) -> f64 {
+        chi_square(&self.counts, self.trials as f64 / self.n as f64)
+    }
+
+    /// Degrees of freedom of chi_square(). Every row and column sums to the number of trials so this is
+    /// (n - 1)^2.
+    pub fn degrees_of_freedom(&self) -> usize {
+        self.n.saturating_sub(1).pow(2)
+    }
+
+    /// Pearson's chi-square statistic for the positions of a single card, which has n - 1 degrees of freedom.
+    pub fn card_chi_square(&self, card: usize) -> f64 {
+        let row = &self.counts[card * self.n..(card + 1) * self.n];
+        chi_square(row, self.trials as f64 / self.n as f64)
+    }
+}
+
+/// The exact probability that k riffle shuffles, as performed by .riffle(), produce the permutation. By
+/// Bayer and Diaconis this depends only on the number of rising sequences r and is C(2^k + n - r, n) / 2^(kn).
+pub fn riffle_probability(perm: &Permutation, k: u32) -> BigRational {
+    riffle_probability_rising(perm.len(), perm.rising_sequences(), k)
+}
+
+/// The exact total variation distance from the uniform distribution after k riffle shuffles of an n card deck,
+/// as performed by .riffle(). Use num_traits::ToPrimitive to convert the result to a float.
+pub fn riffle_total_variation(n: usize, k: u32) -> BigRational {
+    let uniform = BigRational::new(BigInt::one(), factorial(n).into());
+    // Every permutation with the same number of rising sequences is equally likely so the sum over
+    // permutations is grouped by rising sequences, counted by the Eulerian numbers
+    let sum =
+        eulerian(n)
+            .into_iter()
+            .enumerate()
+            .fold(BigRational::zero(), |acc, (descents, count)| {
+                let diff = riffle_probability_rising(n, descents + 1, k) - &uniform;
+                acc + diff.abs() * BigInt::from(count)
+            });
+    sum / BigInt::from(2)
+}
+
+// Probability of any one permutation of n cards with r rising sequences after k riffles.
+fn riffle_probability_rising(n: usize, r: usize, k: u32) -> BigRational {
+    // The binomial coefficient C(2^k + n - r, n) as a falling factorial. When r > 2^k one of the factors is
+    // zero since no sequence of k riffles can make that many rising sequences.
+    let top = (BigInt::one() << k) + n - r;
+    let falling = (0..n).fold(BigInt::one(), |acc, i| acc * (&top - i));
+    let denom = BigInt::from(factorial(n)) << (k as usize * n);
+    BigRational::new(falling, denom)
+}
+
+fn factorial(n: usize) -> BigUint {
+    (1..=n).fold(BigUint::one(), |acc, i| acc * i)
+}
+
+// The Eulerian numbers A(n, m), the number of permutations of n items with m descents, for m in 0..n.
+fn eulerian(n: usize) -> Vec<BigUint> {
+    let mut row = vec![BigUint::one()];
+    for len in 2..=n {
+        let mut next = Vec::with_capacity(len);
+        for m in 0..len {
+            let mut a = BigUint::zero();
+            if m < row.len() {
+                a += &row[m] * (m + 1);
+            }
+            if m > 0 {
+                a += &row[m - 1] * (len - m);
+            }
+            next.push(a);
+        }
+        row = next;
+    }
+    row
+}
+
+fn chi_square(observed: &[u64], expected: f64) -> f64 {
+    observed
+        .iter()
+        .map(|&o| (o as f64 - expected).powi(2) / expected)
+        .sum()
+}
+
+#[cfg(test)]
+mod test_analysis {
+    use super::*;
+    use num_traits::ToPrimitive;
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
+    fn rng() -> Xoshiro256PlusPlus {
+        Xoshiro256PlusPlus::seed_from_u64(314159)
+    }
+
+    #[test]
+    fn identity() {
+        let m = PositionMatrix::estimate(5, 100, rng(), |_| {});
+        assert_eq!(m.count(2, 2), 100);
+        assert_eq!(m.count(2, 3), 0);
+        assert_eq!(m.max_deviation(), 0.8);
+        assert_eq!(m.chi_square(), 2000.0);
+        assert_eq!(m.degrees_of_freedom(), 16);
+    }
+
+    #[test]
+    fn from_permutations() {
+        let mut deck = Deck::from_iter(0..8);
+        deck.set_seed_u64(314159);
+        let observed: Vec<_> = (0..100).map(|_| deck.record(|d| d.riffle())).collect();
+        let m = PositionMatrix::from_permutations(&observed).unwrap();
+        assert_eq!(m.trials(), 100);
+        assert_eq!((0..8).map(|pos| m.count(3, pos)).sum::<u64>(), 100);
+
+        let mixed = [Permutation::identity(3), Permutation::identity(4)];
+        assert!(PositionMatrix::from_permutations(&mixed).is_err());
+    }
+
+    #[test]
+    fn shuffles() {
+        let fisher_yates = PositionMatrix::estimate(10, 20_000, rng(), |d| d.shuffle());
+        let one_riffle = PositionMatrix::estimate(10, 20_000, rng(), |d| d.riffle());
+        let seven_riffles = PositionMatrix::estimate(10, 20_000, rng(), |d| {
+            for _ in 0..7 {
+                d.riffle();
+            }
+        });
+        assert!(fisher_yates.max_deviation() < 0.02);
+        assert!(seven_riffles.max_deviation() < 0.02);
+        assert!(one_riffle.max_deviation() > 0.1);
+        // The expected value of the statistic for a uniform shuffle is the degrees of freedom
+        assert!(fisher_yates.chi_square() < 2.0 * fisher_yates.degrees_of_freedom() as f64);
+        assert!(one_riffle.chi_square() > 10.0 * one_riffle.degrees_of_freedom() as f64);
+        assert!(one_riffle.card_chi_square(0) > fisher_yates.card_chi_square(0));
+
+        let row_sum: f64 = fisher_yates.probabilities()[3].iter().sum();
+        assert!((row_sum - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn total_variation() {
+        // The values from Bayer and Diaconis for a 52 card deck
+        let tv = |k| riffle_total_variation(52, k).to_f64().unwrap();
+        assert!((tv(5) - 0.924).abs() < 0.001);
+        assert!((tv(7) - 0.334).abs() < 0.001);
+        assert!((tv(10) - 0.043).abs() < 0.001);
+        assert_eq!(
+            riffle_total_variation(10, 0),
+            BigRational::new(3628799.into(), 3628800.into())
+        );
+    }
+
+    #[test]
+    fn probabilities() {
+        // The probabilities of every permutation sum to one
+        for k in 0..4 {
+            let total =
+                eulerian(6)
+                    .into_iter()
+                    .enumerate()
+                    .fold(BigRational::zero(), |acc, (m, count)| {
+                        acc + riffle_probability_rising(6, m + 1, k) * BigInt::from(count)
+                    });
+            assert_eq!(total, BigRational::one());
+        }
+        // One riffle of three cards leaves them unchanged with probability 1/2
+        let identity = Permutation::identity(3);
+        assert_eq!(
+            riffle_probability(&identity, 1),
+            BigRational::new(1.into(), 2.into())
+        );
+        // and never produces three rising sequences
+        let reversed = Permutation::new(vec![2, 1, 0]).unwrap();
+        assert_eq!(riffle_probability(&reversed, 1), BigRational::zero());
+    }
+}
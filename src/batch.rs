@@ -0,0 +1,308 @@
+use rand::{RngCore, SeedableRng};
+use rand_xoshiro::Xoshiro256PlusPlus;
+
+use crate::{deck::Deck, error::DeckError};
+
+/// Many decks of the same size stored contiguously with a single RNG. Shuffling them all at once avoids the
+/// overhead of a separate Deck and RNG for each one and draws several swap positions from each random number.
+/// Any seedable RNG can be used, by default this is Xoshiro256PlusPlus.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeckBatch<T, R: RngCore + SeedableRng = Xoshiro256PlusPlus> {
+    cards: Vec<T>,
+    deck_size: usize,
+    rng: R,
+}
+
+// Number of decks in each block given its own RNG stream by .par_shuffle_all().
+#[cfg(feature = "rayon")]
+const PAR_BLOCK: usize = 1024;
+
+// A Fisher-Yates shuffle that gets the swap positions for several steps from one 64-bit random number. The
+// bounds of consecutive steps are multiplied while the product fits in a u64 and a number below the product is
+// drawn by Lemire's multiply and reject method. Multiplying by each bound in turn splits it into one uniform
+// position per step. See Brackett-Rozinsky and Lemire, "Batched Ranged Random Integer Generation".
+fn shuffle_batched<T, R: RngCore>(cards: &mut [T], rng: &mut R) {
+    let mut positions = [0; 64];
+    let mut i = cards.len();
+    while i > 1 {
+        let mut product = i as u64;
+        let mut steps = 1;
+        while i - steps > 1 {
+            match product.checked_mul((i - steps) as u64) {
+                Some(p) => product = p,
+                None => break,
+            }
+            steps += 1;
+        }
+        loop {
+            let mut r = rng.next_u64();
+            for (k, pos) in positions[..steps].iter_mut().enumerate() {
+                let m = r as u128 * (i - k) as u128;
+                *pos = (m >> 64) as usize;
+                r = m as u64;
+            }
+            // Reject the few numbers that would make some positions more likely than others
+            if r >= product || r >= product.wrapping_neg() % product {
+                break;
+            }
+        }
+        for (k, &pos) in positions[..steps].iter().enumerate() {
+            cards.swap(i - 1 - k, pos);
+        }
+        i -= steps;
+    }
+}
+
+impl<T: Clone, R: RngCore + SeedableRng> DeckBatch<T, R> {
+    /// Create a batch of count copies of the deck. The batch RNG is seeded from the deck's RNG.
+    pub fn repeat(deck: &mut Deck<T, R>, count: usize) -> DeckBatch<T, R> {
+        let mut cards = Vec::with_capacity(deck.len() * count);
+        for _ in 0..count {
+            cards.extend(deck.iter().cloned());
+        }
+        DeckBatch {
+            cards,
+            deck_size: deck.len(),
+            rng: R::from_rng(&mut deck.rng).expect("the deck's RNG should not fail"),
+        }
+    }
+}
+
+impl<T, R: RngCore + SeedableRng> DeckBatch<T, R> {
+    /// Gather several decks into a batch. The batch uses the RNG of the first deck. Returns an error if they are
+    /// not all the same size.
+    pub fn from_decks(decks: Vec<Deck<T, R>>) -> Result<DeckBatch<T, R>, DeckError> {
+        let deck_size = decks.first().map(|d| d.len()).unwrap_or(0);
+        let mut cards = Vec::with_capacity(deck_size * decks.len());
+        let mut rng = None;
+        for deck in decks {
+            if deck.len() != deck_size {
+                return Err(DeckError::LengthMismatch {
+                    expected: deck_size,
+                    len: deck.len(),
+                });
+            }
+            cards.extend(deck.cards);
+            rng.get_or_insert(deck.rng);
+        }
+        Ok(DeckBatch {
+            cards,
+            deck_size,
+            rng: rng.unwrap_or_else(R::from_entropy),
+        })
+    }
+
+    /// Supply a seed for the RNG. For the default RNG this is 256 bits of state.
+    pub fn set_seed(&mut self, seed: R::Seed) {
+        self.rng = R::from_seed(seed)
+    }
+
+    /// Seed the internal RNG from a u64. Should be used only for testing.
+    pub fn set_seed_u64(&mut self, seed: u64) {
+        self.rng = R::seed_from_u64(seed)
+    }
+
+    /// Number of decks in the batch.
+    pub fn len(&self) -> usize {
+        match self.deck_size {
+            0 => 0,
+            n => self.cards.len() / n,
+        }
+    }
+
+    /// True if there are no decks in the batch.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of cards in each deck.
+    pub fn deck_size(&self) -> usize {
+        self.deck_size
+    }
+
+    /// The cards of the nth deck from top to bottom.
+    pub fn get(&self, n: usize) -> Option<&[T]> {
+        self.iter().nth(n)
+    }
+
+    /// The cards of the nth deck from top to bottom, mutably.
+    pub fn get_mut(&mut self, n: usize) -> Option<&mut [T]> {
+        self.iter_mut().nth(n)
+    }
+
+    /// An iterator over the cards of each deck.
+    pub fn iter(&self) -> std::slice::ChunksExact<'_, T> {
+        self.cards.chunks_exact(self.deck_size.max(1))
+    }
+
+    /// An iterator over the cards of each deck, mutably.
+    pub fn iter_mut(&mut self) -> std::slice::ChunksExactMut<'_, T> {
+        self.cards.chunks_exact_mut(self.deck_size.max(1))
+    }
+
+    /// Separate the batch into individual decks. Each deck gets an RNG seeded from the batch RNG.
+    pub fn into_decks(mut self) -> Vec<Deck<T, R>> {
+        let count = self.len();
+        let mut out = Vec::with_capacity(count);
+        let mut cards = self.cards.into_iter();
+        for _ in 0..count {
+            let rng = R::from_rng(&mut self.rng).expect("the batch's RNG should not fail");
+            out.push(Deck::with_rng(
+                cards.by_ref().take(self.deck_size).collect::<Vec<T>>(),
+                rng,
+            ));
+        }
+        out
+    }
+
+    /// Perform a Fisher-Yates shuffle on every deck in the batch.
+    pub fn shuffle_all(&mut self) {
+        let size = self.deck_size.max(1);
+        for deck in self.cards.chunks_exact_mut(size) {
+            shuffle_batched(deck, &mut self.rng);
+        }
+    }
+}
+
+// Parallel shuffling needs to jump the RNG so it is only provided for the default RNG.
+#[cfg(feature = "rayon")]
+impl<T: Send> DeckBatch<T> {
+    /// Perform a Fisher-Yates shuffle on every deck in the batch in parallel. Each block of decks uses its own
+    /// stream made by jumping the RNG so the result is the same for any number of threads, though it differs
+    /// from .shuffle_all(). Afterwards the batch RNG is moved past every stream that was used.
+    pub fn par_shuffle_all(&mut self) {
+        use rayon::prelude::*;
+
+        let size = self.deck_size.max(1);
+        let blocks = self.cards.len().div_ceil(size * PAR_BLOCK);
+        let mut rngs = Vec::with_capacity(blocks);
+        let mut rng = self.rng.clone();
+        for _ in 0..blocks {
+            rng.jump();
+            rngs.push(rng.clone());
+        }
+        self.cards
+            .par_chunks_mut(size * PAR_BLOCK)
+            .zip(rngs)
+            .for_each(|(block, mut rng)| {
+                for deck in block.chunks_exact_mut(size) {
+                    shuffle_batched(deck, &mut rng);
+                }
+            });
+        self.rng.long_jump();
+    }
+}
+
+#[cfg(test)]
+mod test_batch {
+    use super::*;
+
+    #[test]
+    fn shuffle_all() {
+        let mut batch = DeckBatch::repeat(&mut Deck::from_iter(0..52), 100);
+        batch.set_seed_u64(314159);
+        let mut other = batch.clone();
+        batch.shuffle_all();
+        other.shuffle_all();
+        assert_eq!(batch, other);
+        assert_eq!(batch.len(), 100);
+        for deck in batch.iter() {
+            let mut deck = deck.to_vec();
+            deck.sort();
+            assert_eq!(deck, (0..52).collect::<Vec<_>>());
+        }
+        assert_ne!(batch.get(0), batch.get(1));
+
+        // A batch made from a seeded deck is reproducible
+        let seeded = || {
+            let mut deck = Deck::from_iter(0..52);
+            deck.set_seed_u64(314159);
+            let mut batch = DeckBatch::repeat(&mut deck, 10);
+            batch.shuffle_all();
+            batch
+        };
+        assert_eq!(seeded(), seeded());
+    }
+
+    #[test]
+    fn shuffle_all_uniform() {
+        // Every order of a small deck is equally likely
+        let mut batch = DeckBatch::repeat(&mut Deck::from([0, 1, 2]), 60_000);
+        batch.set_seed_u64(314159);
+        batch.shuffle_all();
+        let mut counts = std::collections::HashMap::new();
+        for deck in batch.iter() {
+            *counts.entry(deck.to_vec()).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 6);
+        assert!(counts.values().all(|&c| (9_500..10_500).contains(&c)));
+
+        // In a large deck several steps share each draw and every card is equally likely to end on top
+        let mut batch = DeckBatch::repeat(&mut Deck::from_iter(0..52), 52_000);
+        batch.set_seed_u64(314159);
+        batch.shuffle_all();
+        let mut tops = [0; 52];
+        for deck in batch.iter() {
+            tops[deck[0]] += 1;
+        }
+        assert!(tops.iter().all(|&c| (800..1_200).contains(&c)));
+    }
+
+    #[test]
+    fn from_decks() {
+        let batch = DeckBatch::from_decks(vec![Deck::from([0, 1]), Deck::from([2, 3])]).unwrap();
+        assert_eq!(batch.get(1), Some(&[2, 3][..]));
+        let decks = batch.into_decks();
+        assert_eq!(decks[0].cards, [0, 1]);
+        assert_eq!(decks[1].cards, [2, 3]);
+
+        // The batch keeps the first deck's RNG and seeds the separated decks from it
+        let seeded = || {
+            let mut first = Deck::from([0, 1]);
+            first.set_seed_u64(314159);
+            DeckBatch::from_decks(vec![first, Deck::from([2, 3])]).unwrap()
+        };
+        let (mut a, mut b) = (seeded(), seeded());
+        a.shuffle_all();
+        b.shuffle_all();
+        assert_eq!(a, b);
+        let mut a = a.into_decks();
+        let mut b = b.into_decks();
+        a[1].shuffle();
+        b[1].shuffle();
+        assert_eq!(a, b);
+
+        assert_eq!(
+            DeckBatch::from_decks(vec![Deck::from([0, 1]), Deck::from([2])]),
+            Err(DeckError::LengthMismatch {
+                expected: 2,
+                len: 1
+            })
+        );
+    }
+
+    #[test]
+    fn other_rng() {
+        let mut deck = Deck::with_rng(vec![0, 1, 2, 3], rand::rngs::StdRng::seed_from_u64(314159));
+        let mut batch = DeckBatch::repeat(&mut deck, 10);
+        batch.shuffle_all();
+        let decks: Vec<Deck<i32, rand::rngs::StdRng>> = batch.into_decks();
+        assert_eq!(decks.len(), 10);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_shuffle_all() {
+        let run = |threads| {
+            let mut batch = DeckBatch::repeat(&mut Deck::from_iter(0..52), 5000);
+            batch.set_seed_u64(314159);
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| batch.par_shuffle_all());
+            batch
+        };
+        assert_eq!(run(1), run(4));
+    }
+}
@@ -0,0 +1,322 @@
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use crate::deck::Deck;
+
+/// The four French suits, ordered as in bridge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+    /// True for Diamonds and Hearts.
+    pub fn is_red(&self) -> bool {
+        matches!(self, Suit::Diamonds | Suit::Hearts)
+    }
+
+    /// True for Clubs and Spades.
+    pub fn is_black(&self) -> bool {
+        !self.is_red()
+    }
+}
+
+impl Display for Suit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Suit::Clubs => "C",
+            Suit::Diamonds => "D",
+            Suit::Hearts => "H",
+            Suit::Spades => "S",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    /// Accepts the initial of the suit in either case or the suit symbol.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "C" | "c" | "♣" | "♧" => Ok(Suit::Clubs),
+            "D" | "d" | "♦" | "♢" => Ok(Suit::Diamonds),
+            "H" | "h" | "♥" | "♡" => Ok(Suit::Hearts),
+            "S" | "s" | "♠" | "♤" => Ok(Suit::Spades),
+            _ => Err(ParseCardError(s.to_string())),
+        }
+    }
+}
+
+/// The thirteen ranks, ordered with Ace high.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rank {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    /// True for Jack, Queen, and King.
+    pub fn is_face(&self) -> bool {
+        matches!(self, Rank::Jack | Rank::Queen | Rank::King)
+    }
+}
+
+impl Display for Rank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Ace => "A",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for Rank {
+    type Err = ParseCardError;
+
+    /// Accepts the number of the rank or the initial of a face card or ace in either case. Ten may be written
+    /// as "10" or "T".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2" => Ok(Rank::Two),
+            "3" => Ok(Rank::Three),
+            "4" => Ok(Rank::Four),
+            "5" => Ok(Rank::Five),
+            "6" => Ok(Rank::Six),
+            "7" => Ok(Rank::Seven),
+            "8" => Ok(Rank::Eight),
+            "9" => Ok(Rank::Nine),
+            "10" | "T" | "t" => Ok(Rank::Ten),
+            "J" | "j" => Ok(Rank::Jack),
+            "Q" | "q" => Ok(Rank::Queen),
+            "K" | "k" => Ok(Rank::King),
+            "A" | "a" => Ok(Rank::Ace),
+            _ => Err(ParseCardError(s.to_string())),
+        }
+    }
+}
+
+/// The two jokers found in a 54 card deck.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Joker {
+    Black,
+    Red,
+}
+
+impl Display for Joker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Joker::Black => write!(f, "BJ"),
+            Joker::Red => write!(f, "RJ"),
+        }
+    }
+}
+
+/// A French playing card. Cards are ordered by suit and then by rank with the jokers last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Card {
+    Suited { suit: Suit, rank: Rank },
+    Joker(Joker),
+}
+
+impl Card {
+    /// Create a card with the given rank and suit.
+    pub fn new(rank: Rank, suit: Suit) -> Card {
+        Card::Suited { suit, rank }
+    }
+
+    /// The rank of the card, None for a joker.
+    pub fn rank(&self) -> Option<Rank> {
+        match self {
+            Card::Suited { rank, .. } => Some(*rank),
+            Card::Joker(_) => None,
+        }
+    }
+
+    /// The suit of the card, None for a joker.
+    pub fn suit(&self) -> Option<Suit> {
+        match self {
+            Card::Suited { suit, .. } => Some(*suit),
+            Card::Joker(_) => None,
+        }
+    }
+
+    /// True if the card is a joker.
+    pub fn is_joker(&self) -> bool {
+        matches!(self, Card::Joker(_))
+    }
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Card::Suited { suit, rank } => write!(f, "{}{}", rank, suit),
+            Card::Joker(joker) => write!(f, "{}", joker),
+        }
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    /// Parse a card written as its rank followed by its suit, such as "AS", "10h", or "Td". The jokers are
+    /// written "RJ" and "BJ".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "RJ" | "rj" => return Ok(Card::Joker(Joker::Red)),
+            "BJ" | "bj" => return Ok(Card::Joker(Joker::Black)),
+            _ => (),
+        }
+        let (split, _) = s
+            .char_indices()
+            .last()
+            .ok_or_else(|| ParseCardError(s.to_string()))?;
+        let (rank, suit) = s.split_at(split);
+        match (Rank::from_str(rank), Suit::from_str(suit)) {
+            (Ok(rank), Ok(suit)) => Ok(Card::new(rank, suit)),
+            _ => Err(ParseCardError(s.to_string())),
+        }
+    }
+}
+
+/// The error returned when a string cannot be parsed as a card, rank, or suit. Contains the invalid string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCardError(pub String);
+
+impl Display for ParseCardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\" is not a valid card", self.0)
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+// Every suit of the given ranks, sorted.
+fn suited(ranks: &[Rank]) -> impl Iterator<Item = Card> + '_ {
+    Suit::ALL
+        .into_iter()
+        .flat_map(move |suit| ranks.iter().map(move |rank| Card::new(*rank, suit)))
+}
+
+impl Deck<Card> {
+    /// A standard 52 card deck, sorted.
+    pub fn standard_52() -> Deck<Card> {
+        Deck::from_iter(suited(&Rank::ALL))
+    }
+
+    /// A standard 52 card deck plus the two jokers, sorted.
+    pub fn standard_54() -> Deck<Card> {
+        Deck::from_iter(
+            suited(&Rank::ALL).chain([Card::Joker(Joker::Black), Card::Joker(Joker::Red)]),
+        )
+    }
+
+    /// A 24 card euchre deck with the ranks Nine through Ace, sorted.
+    pub fn euchre() -> Deck<Card> {
+        Deck::from_iter(suited(&Rank::ALL[7..]))
+    }
+
+    /// A 32 card piquet deck with the ranks Seven through Ace, sorted.
+    pub fn piquet_32() -> Deck<Card> {
+        Deck::from_iter(suited(&Rank::ALL[5..]))
+    }
+
+    /// A 48 card pinochle deck with two copies of each card from Nine through Ace, sorted.
+    pub fn pinochle() -> Deck<Card> {
+        Deck::from_iter(suited(&Rank::ALL[7..]).flat_map(|card| [card, card]))
+    }
+}
+
+#[cfg(test)]
+mod test_cards {
+    use super::*;
+
+    #[test]
+    fn deck_sizes() {
+        assert_eq!(Deck::standard_52().len(), 52);
+        assert_eq!(Deck::standard_54().len(), 54);
+        assert_eq!(Deck::euchre().len(), 24);
+        assert_eq!(Deck::piquet_32().len(), 32);
+        assert_eq!(Deck::pinochle().len(), 48);
+    }
+
+    #[test]
+    fn sorted() {
+        let mut deck = Deck::standard_54();
+        deck.shuffle();
+        deck.sort();
+        assert_eq!(deck.cards, Deck::standard_54().cards);
+        assert_eq!(deck.top(), Some(&Card::new(Rank::Two, Suit::Clubs)));
+        assert_eq!(deck.bottom(), Some(&Card::Joker(Joker::Red)));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("AS".parse(), Ok(Card::new(Rank::Ace, Suit::Spades)));
+        assert_eq!("10h".parse(), Ok(Card::new(Rank::Ten, Suit::Hearts)));
+        assert_eq!("Td".parse(), Ok(Card::new(Rank::Ten, Suit::Diamonds)));
+        assert_eq!("Q♣".parse(), Ok(Card::new(Rank::Queen, Suit::Clubs)));
+        assert_eq!("RJ".parse(), Ok(Card::Joker(Joker::Red)));
+        assert!("1S".parse::<Card>().is_err());
+        assert!("AX".parse::<Card>().is_err());
+        assert!("".parse::<Card>().is_err());
+    }
+
+    #[test]
+    fn display() {
+        for card in Deck::standard_54() {
+            assert_eq!(card.to_string().parse(), Ok(card));
+        }
+        assert_eq!(Card::new(Rank::Ten, Suit::Hearts).to_string(), "10H");
+    }
+}
@@ -0,0 +1,170 @@
+use rand::{RngCore, SeedableRng};
+use std::collections::VecDeque;
+
+use crate::{deck::Deck, error::DeckError};
+
+/// The largest number of players a deal accepts. Every player gets a hand even when no cards are dealt so this
+/// bounds the memory used for the hands.
+pub const MAX_PLAYERS: usize = 1 << 16;
+
+impl<T, R: RngCore + SeedableRng> Deck<T, R> {
+    // Dealing either happens completely or not at all. A total that overflows is always more than the deck
+    // holds and is reported as usize::MAX.
+    fn check_deal(&self, players: usize, cards_each: Option<usize>) -> Result<(), DeckError> {
+        if players > MAX_PLAYERS {
+            return Err(DeckError::TooManyPlayers {
+                players,
+                max: MAX_PLAYERS,
+            });
+        }
+        let needed = cards_each
+            .and_then(|c| players.checked_mul(c))
+            .unwrap_or(usize::MAX);
+        if needed > self.len() {
+            return Err(DeckError::NotEnoughCards {
+                needed,
+                len: self.len(),
+            });
+        }
+        Ok(())
+    }
+
+    // Each hand gets an RNG seeded from this deck's RNG.
+    fn empty_hands(&mut self, players: usize) -> Vec<Deck<T, R>> {
+        (0..players).map(|_| self.child(VecDeque::new())).collect()
+    }
+
+    /// Deal cards from the top of the deck one at a time to each player in turn until each has cards_each
+    /// cards. As with a physical deal each card is placed on top of the hand so the first card dealt to a
+    /// player ends up at the bottom of their hand. Returns an error without dealing if there are not enough
+    /// cards or more than MAX_PLAYERS players.
+    pub fn deal(
+        &mut self,
+        players: usize,
+        cards_each: usize,
+    ) -> Result<Vec<Deck<T, R>>, DeckError> {
+        self.check_deal(players, Some(cards_each))?;
+        let mut hands = self.empty_hands(players);
+        for _ in 0..cards_each {
+            for hand in hands.iter_mut() {
+                hand.place_top(self.draw_top().unwrap());
+            }
+        }
+        Ok(hands)
+    }
+
+    /// Deal cards from the bottom of the deck one at a time to each player in turn until each has cards_each
+    /// cards. Returns an error without dealing if there are not enough cards or more than MAX_PLAYERS
+    /// players.
+    pub fn deal_bottom(
+        &mut self,
+        players: usize,
+        cards_each: usize,
+    ) -> Result<Vec<Deck<T, R>>, DeckError> {
+        self.check_deal(players, Some(cards_each))?;
+        let mut hands = self.empty_hands(players);
+        for _ in 0..cards_each {
+            for hand in hands.iter_mut() {
+                hand.place_top(self.draw_bottom().unwrap());
+            }
+        }
+        Ok(hands)
+    }
+
+    /// Deal packets of cards from the top of the deck. In each round every player in turn receives a packet
+    /// with the size given for that round, so &[3, 2, 3] deals three rounds and eight cards to each player.
+    /// Each packet keeps its order and is placed on top of the hand. Returns an error without dealing if there
+    /// are not enough cards or more than MAX_PLAYERS players.
+    pub fn deal_packets(
+        &mut self,
+        players: usize,
+        packets: &[usize],
+    ) -> Result<Vec<Deck<T, R>>, DeckError> {
+        let per_player = packets
+            .iter()
+            .try_fold(0usize, |acc, &p| acc.checked_add(p));
+        self.check_deal(players, per_player)?;
+        let mut hands = self.empty_hands(players);
+        for size in packets {
+            for hand in hands.iter_mut() {
+                for card in self.cards.drain(..size).rev() {
+                    hand.place_top(card);
+                }
+            }
+        }
+        Ok(hands)
+    }
+}
+
+#[cfg(test)]
+mod test_dealing {
+    use super::*;
+
+    #[test]
+    fn deal() {
+        let mut deck = Deck::from_iter(0..10);
+        let hands = deck.deal(3, 3).unwrap();
+        assert_eq!(hands[0].cards, [6, 3, 0]);
+        assert_eq!(hands[1].cards, [7, 4, 1]);
+        assert_eq!(hands[2].cards, [8, 5, 2]);
+        assert_eq!(deck.cards, [9]);
+    }
+
+    #[test]
+    fn deal_bottom() {
+        let mut deck = Deck::from_iter(0..10);
+        let hands = deck.deal_bottom(2, 2).unwrap();
+        assert_eq!(hands[0].cards, [7, 9]);
+        assert_eq!(hands[1].cards, [6, 8]);
+        assert_eq!(deck.cards, [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn deal_packets() {
+        let mut deck = Deck::from_iter(0..10);
+        let hands = deck.deal_packets(2, &[3, 2]).unwrap();
+        assert_eq!(hands[0].cards, [6, 7, 0, 1, 2]);
+        assert_eq!(hands[1].cards, [8, 9, 3, 4, 5]);
+        assert!(deck.is_empty());
+    }
+
+    #[test]
+    fn not_enough_cards() {
+        let mut deck = Deck::from_iter(0..10);
+        assert_eq!(
+            deck.deal(4, 3),
+            Err(DeckError::NotEnoughCards {
+                needed: 12,
+                len: 10
+            })
+        );
+        assert!(deck.deal_packets(2, &[3, 3]).is_err());
+        assert_eq!(deck.len(), 10);
+
+        // Totals that overflow and huge numbers of players are rejected before allocating
+        assert!(deck.deal(usize::MAX, 2).is_err());
+        assert_eq!(
+            deck.deal(usize::MAX / 64, 0),
+            Err(DeckError::TooManyPlayers {
+                players: usize::MAX / 64,
+                max: MAX_PLAYERS
+            })
+        );
+        assert_eq!(
+            deck.deal_bottom(2, usize::MAX),
+            Err(DeckError::NotEnoughCards {
+                needed: usize::MAX,
+                len: 10
+            })
+        );
+        assert!(deck.deal_packets(2, &[usize::MAX, 2]).is_err());
+        assert_eq!(deck.len(), 10);
+
+        // Dealing no cards needs no cards, even to more players than there are cards
+        let mut deck = Deck::from_iter(0..3);
+        assert_eq!(deck.deal(5, 0).map(|h| h.len()), Ok(5));
+        assert_eq!(deck.deal_packets(5, &[]).map(|h| h.len()), Ok(5));
+        assert_eq!(deck.deal_packets(5, &[0, 0]).map(|h| h.len()), Ok(5));
+        assert_eq!(deck.len(), 3);
+    }
+}
@@ -1,19 +1,82 @@
-use rand::{Rng, SeedableRng};
+use rand::{Rng, RngCore, SeedableRng};
 use rand_distr::{Binomial, Distribution};
 use rand_xoshiro::Xoshiro256PlusPlus;
+
+use crate::error::DeckError;
 use std::{
     collections::VecDeque,
     fmt::{Debug, Display},
 };
 
 #[derive(Debug, Clone, PartialEq)]
-/// A deque_deck Deck is simply a VecDeque and an RNG
-pub struct Deck<T> {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A deque_deck Deck is simply a VecDeque and an RNG. Any seedable RNG can be used, by default this is Xoshiro256PlusPlus.
+pub struct Deck<T, R: RngCore + SeedableRng = Xoshiro256PlusPlus> {
     pub cards: VecDeque<T>,
-    pub(crate) rng: Xoshiro256PlusPlus,
+    pub(crate) rng: R,
 }
 
+// Constructors that do not take an RNG are only provided for the default RNG so that the type of the RNG can
+// always be inferred.
 impl<T> Deck<T> {
+    /// Create an empty deck.
+    pub fn empty() -> Deck<T> {
+        Deck::from(VecDeque::new())
+    }
+
+    /// Create an empty deck with a specific amount of capacity allocated.
+    pub fn with_capacity(n: usize) -> Deck<T> {
+        Deck::from(VecDeque::with_capacity(n))
+    }
+
+    /// Jump the internal RNG forward by 2^128 steps.
+    pub fn jump(&mut self) {
+        self.rng.jump()
+    }
+
+    /// Jump the internal RNG forward by 2^192 steps.
+    pub fn long_jump(&mut self) {
+        self.rng.long_jump()
+    }
+
+    /// Capture the 256 bits of state of the internal RNG. Restoring this state later continues the exact same
+    /// sequence of random numbers. Requires the serde feature.
+    #[cfg(feature = "serde")]
+    pub fn rng_state(&self) -> [u8; 32] {
+        // The state of the RNG is private but it serializes as four u64s which bincode writes as 32 little
+        // endian bytes, the same layout that from_seed reads the state from.
+        bincode::serialize(&self.rng)
+            .expect("RNG state should serialize")
+            .try_into()
+            .expect("RNG state should be 32 bytes")
+    }
+
+    /// Restore the internal RNG to a state captured by .rng_state(). Requires the serde feature.
+    #[cfg(feature = "serde")]
+    pub fn restore_rng_state(&mut self, state: [u8; 32]) {
+        self.rng = Xoshiro256PlusPlus::from_seed(state)
+    }
+}
+
+impl<T, R: RngCore + SeedableRng> Deck<T, R> {
+    // New decks made from this one get an RNG seeded from this deck's RNG so a seeded deck stays
+    // reproducible through splits and deals.
+    pub(crate) fn child(&mut self, cards: VecDeque<T>) -> Deck<T, R> {
+        Deck {
+            cards,
+            rng: R::from_rng(&mut self.rng).expect("the deck's RNG should not fail"),
+        }
+    }
+
+    // Split off cards that a shuffle will immediately take back. Their RNG is never used so it is not derived
+    // from this deck's RNG, which would change the sequence of random numbers the shuffle sees.
+    pub(crate) fn split_off_packet(&mut self, n: usize) -> Deck<T, R> {
+        Deck {
+            cards: self.cards.split_off(n),
+            rng: R::from_seed(R::Seed::default()),
+        }
+    }
+
     // This is used to simulate a human making a selection of where to cut a deck. Strictly speaking humans
     // are much more biased than a binomial distribution. However for a 52 card deck there is a 99.98%
     // probability of selecting in the middle half and a 93% chance of selecting in the middle quarter of
@@ -27,29 +90,40 @@ impl<T> Deck<T> {
         self.rng.gen_range(0..self.cards.len())
     }
 
-    /// Create an empty deck.
-    pub fn empty() -> Deck<T> {
-        Deck::from(VecDeque::new())
+    pub(crate) fn try_uniform(&mut self) -> Result<usize, DeckError> {
+        if self.is_empty() {
+            return Err(DeckError::EmptyDeck);
+        }
+        Ok(self.uniform())
     }
 
-    /// Create an empty deck with a specific amount of capacity allocated.
-    pub fn with_capacity(n: usize) -> Deck<T> {
-        Deck::from(VecDeque::with_capacity(n))
+    // Check that n is a valid position to insert at or split at, this includes the position after the last card.
+    pub(crate) fn check_position(&self, n: usize) -> Result<(), DeckError> {
+        if n > self.len() {
+            return Err(DeckError::IndexOutOfBounds {
+                index: n,
+                len: self.len(),
+            });
+        }
+        Ok(())
     }
 
-    /// Supply 256 bits of state for the RNG
-    pub fn set_seed(&mut self, seed: [u8; 32]) {
-        self.rng = Xoshiro256PlusPlus::from_seed(seed)
+    /// Create a deck from any collection of cards and an RNG of any type.
+    pub fn with_rng<C: Into<VecDeque<T>>>(cards: C, rng: R) -> Deck<T, R> {
+        Deck {
+            cards: cards.into(),
+            rng,
+        }
     }
 
-    /// Seed the internal RNG from a u64. Should be used only for testing.
-    pub fn set_seed_u64(&mut self, seed: u64) {
-        self.rng = Xoshiro256PlusPlus::seed_from_u64(seed)
+    /// Supply a seed for the RNG. For the default RNG this is 256 bits of state.
+    pub fn set_seed(&mut self, seed: R::Seed) {
+        self.rng = R::from_seed(seed)
     }
 
-    /// Jump the internal RNG forward by 2^128 steps.
-    pub fn jump(&mut self) {
-        self.rng.jump()
+    /// Seed the internal RNG from a u64. Should be used only for testing.
+    pub fn set_seed_u64(&mut self, seed: u64) {
+        self.rng = R::seed_from_u64(seed)
     }
 
     /// Number of cards in the Deck.
@@ -57,8 +131,13 @@ impl<T> Deck<T> {
         self.cards.len()
     }
 
+    /// True if there are no cards in the Deck.
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
     /// Append the Deck with the cards of another Deck, consuming the other. The other deck is placed below this one.
-    pub fn extend(&mut self, other: Deck<T>) {
+    pub fn extend<S: RngCore + SeedableRng>(&mut self, other: Deck<T, S>) {
         self.cards.extend(other.cards)
     }
 
@@ -73,19 +152,24 @@ impl<T> Deck<T> {
     }
 
     /// A front to back iterator of references.
-    pub fn iter(&self) -> std::collections::vec_deque::Iter<T> {
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, T> {
         self.cards.iter()
     }
 
     /// A front to back iterator of mutable references.
-    pub fn iter_mut(&mut self) -> std::collections::vec_deque::IterMut<T> {
+    pub fn iter_mut(&mut self) -> std::collections::vec_deque::IterMut<'_, T> {
         self.cards.iter_mut()
     }
 
     /// Swap cards i and j, returns an error if either is out of bounds.
-    pub fn swap(&mut self, i: usize, j: usize) -> Result<(), &'static str> {
-        if i >= self.len() || j >= self.len() {
-            return Err("index out of bounds");
+    pub fn swap(&mut self, i: usize, j: usize) -> Result<(), DeckError> {
+        for index in [i, j] {
+            if index >= self.len() {
+                return Err(DeckError::IndexOutOfBounds {
+                    index,
+                    len: self.len(),
+                });
+            }
         }
         self.cards.swap(i, j);
         Ok(())
@@ -136,12 +220,18 @@ impl<T> Deck<T> {
         self.cards.remove(n)
     }
 
-    /// Draw a uniformly random card from the deck.
+    /// Draw a uniformly random card from the deck. Panics if the deck is empty.
     pub fn draw_random(&mut self) -> Option<T> {
         let n = self.uniform();
         self.draw_nth(n)
     }
 
+    /// Draw a uniformly random card from the deck, returns an error if the deck is empty.
+    pub fn try_draw_random(&mut self) -> Result<T, DeckError> {
+        let n = self.try_uniform()?;
+        self.draw_nth(n).ok_or(DeckError::EmptyDeck)
+    }
+
     /// Draw a card from the deck following a binomial distribution.
     pub fn draw_binom(&mut self) -> Option<T> {
         let n = self.binom();
@@ -163,76 +253,141 @@ impl<T> Deck<T> {
         self.cards.insert(n, card);
     }
 
-    /// Place the card at a random position in the deck.
+    /// Place the card in the nth position in the deck. 0 places it on the top. Returns an error if n is an invalid index.
+    pub fn try_place_nth(&mut self, n: usize, card: T) -> Result<(), DeckError> {
+        self.check_position(n)?;
+        self.place_nth(n, card);
+        Ok(())
+    }
+
+    /// Place the card at a random position in the deck. Panics if the deck is empty.
     pub fn place_random(&mut self, card: T) {
         let n = self.uniform();
         self.place_nth(n, card);
     }
 
+    /// Place the card at a random position in the deck, returns an error if the deck is empty.
+    pub fn try_place_random(&mut self, card: T) -> Result<(), DeckError> {
+        let n = self.try_uniform()?;
+        self.place_nth(n, card);
+        Ok(())
+    }
+
     /// Place a card into the deck following a binomial distribution.
     pub fn place_binom(&mut self, card: T) {
         let n = self.binom();
         self.place_nth(n, card);
     }
 
-    /// Cut the deck at nth position.
+    /// Cut the deck at nth position. Panics if n is greater than the number of cards.
     pub fn cut_nth(&mut self, n: usize) {
         self.cards.rotate_left(n)
     }
 
-    /// Cut the deck at a random position.
+    /// Cut the deck at nth position, returns an error if n is greater than the number of cards.
+    pub fn try_cut_nth(&mut self, n: usize) -> Result<(), DeckError> {
+        self.check_position(n)?;
+        self.cut_nth(n);
+        Ok(())
+    }
+
+    /// Cut the deck at a random position. Panics if the deck is empty.
     pub fn cut_random(&mut self) {
         let n = self.uniform();
         self.cards.rotate_left(n)
     }
 
+    /// Cut the deck at a random position, returns an error if the deck is empty.
+    pub fn try_cut_random(&mut self) -> Result<(), DeckError> {
+        let n = self.try_uniform()?;
+        self.cut_nth(n);
+        Ok(())
+    }
+
     /// Cut the deck following a binomial distribution.
     pub fn cut_binom(&mut self) {
         let n = self.binom();
         self.cards.rotate_left(n)
     }
 
-    /// Split the deck at the nth position, retaining the top part.
-    pub fn split_off_nth(&mut self, n: usize) -> Deck<T> {
-        Deck::from(self.cards.split_off(n))
+    /// Split the deck at the nth position, retaining the top part. The new deck has its RNG seeded from this
+    /// deck's RNG. Panics if n is greater than the number of cards.
+    pub fn split_off_nth(&mut self, n: usize) -> Deck<T, R> {
+        let bottom = self.cards.split_off(n);
+        self.child(bottom)
     }
 
-    /// Split the deck at a random position, retaining the top part.
-    pub fn split_off_random(&mut self) -> Deck<T> {
+    /// Split the deck at the nth position, retaining the top part. Returns an error if n is greater than the
+    /// number of cards.
+    pub fn try_split_off_nth(&mut self, n: usize) -> Result<Deck<T, R>, DeckError> {
+        self.check_position(n)?;
+        Ok(self.split_off_nth(n))
+    }
+
+    /// Split the deck at a random position, retaining the top part. Panics if the deck is empty.
+    pub fn split_off_random(&mut self) -> Deck<T, R> {
         let n = self.uniform();
         self.split_off_nth(n)
     }
 
+    /// Split the deck at a random position, retaining the top part. Returns an error if the deck is empty.
+    pub fn try_split_off_random(&mut self) -> Result<Deck<T, R>, DeckError> {
+        let n = self.try_uniform()?;
+        Ok(self.split_off_nth(n))
+    }
+
     /// Split the deck following a binomial distribution, retaining the top part.
-    pub fn split_off_binom(&mut self) -> Deck<T> {
+    pub fn split_off_binom(&mut self) -> Deck<T, R> {
         let n = self.binom();
         self.split_off_nth(n)
     }
 
-    /// Split the deck at the nth position, consuming it.
-    pub fn split_nth(mut self, n: usize) -> (Deck<T>, Deck<T>) {
-        let cards = self.cards.split_off(n);
-        (Deck::from(self.cards), Deck::from(cards))
+    /// Split the deck at the nth position, consuming it. The top part keeps the RNG of the original deck and
+    /// the bottom part has its RNG seeded from it. Panics if n is greater than the number of cards.
+    pub fn split_nth(mut self, n: usize) -> (Deck<T, R>, Deck<T, R>) {
+        let bottom = self.split_off_nth(n);
+        (self, bottom)
+    }
+
+    /// Split the deck at the nth position, consuming it. If n is greater than the number of cards the deck is
+    /// returned unchanged along with the error.
+    pub fn try_split_nth(self, n: usize) -> Result<(Self, Self), (Self, DeckError)> {
+        match self.check_position(n) {
+            Ok(()) => Ok(self.split_nth(n)),
+            Err(e) => Err((self, e)),
+        }
     }
 
-    /// Split the deck at a random position, consuming it.
-    pub fn split_random(mut self) -> (Deck<T>, Deck<T>) {
+    /// Split the deck at a random position, consuming it. Panics if the deck is empty.
+    pub fn split_random(mut self) -> (Deck<T, R>, Deck<T, R>) {
         let n = self.uniform();
         self.split_nth(n)
     }
 
+    /// Split the deck at a random position, consuming it. If the deck is empty it is returned along with the
+    /// error.
+    pub fn try_split_random(mut self) -> Result<(Self, Self), (Self, DeckError)> {
+        match self.try_uniform() {
+            Ok(n) => Ok(self.split_nth(n)),
+            Err(e) => Err((self, e)),
+        }
+    }
+
     /// Split the deck following a binomial distribution, consuming it.
-    pub fn split_binom(mut self) -> (Deck<T>, Deck<T>) {
+    pub fn split_binom(mut self) -> (Deck<T, R>, Deck<T, R>) {
         let n = self.binom();
         self.split_nth(n)
     }
 
     /// Perform a cycle permutation on the cards of the Deck. If any index
     /// is invalid an error is returned before any swaps are made.
-    pub fn cycle(&mut self, cycle: &[usize]) -> Result<(), &'static str> {
+    pub fn cycle(&mut self, cycle: &[usize]) -> Result<(), DeckError> {
         for pos in cycle {
             if *pos >= self.len() {
-                return Err("index out of bounds");
+                return Err(DeckError::IndexOutOfBounds {
+                    index: *pos,
+                    len: self.len(),
+                });
             }
         }
         for pair in cycle.windows(2) {
@@ -242,18 +397,14 @@ impl<T> Deck<T> {
     }
 }
 
-impl<T: Display> Display for Deck<T> {
+impl<T: Display, R: RngCore + SeedableRng> Display for Deck<T, R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut list = format!("[{}", self.cards[0]);
-        for card in self.cards.iter().skip(1) {
-            list.push_str(", ");
-            list.push_str(&card.to_string());
-        }
-        write!(f, "{}]", list)
+        let list: Vec<String> = self.cards.iter().map(|card| card.to_string()).collect();
+        write!(f, "[{}]", list.join(", "))
     }
 }
 
-impl<T: Ord> Deck<T> {
+impl<T: Ord, R: RngCore + SeedableRng> Deck<T, R> {
     pub fn sort(&mut self) {
         self.cards.make_contiguous().sort()
     }
@@ -280,7 +431,7 @@ impl<T> From<Vec<T>> for Deck<T> {
 impl<T> From<VecDeque<T>> for Deck<T> {
     fn from(vec: VecDeque<T>) -> Self {
         Deck {
-            cards: VecDeque::from(vec),
+            cards: vec,
             rng: Xoshiro256PlusPlus::from_entropy(),
         }
     }
@@ -299,29 +450,41 @@ impl<T> FromIterator<T> for Deck<T> {
     }
 }
 
-/// Gather a Vec of several Decks into a single deck.
-impl<T> From<Vec<Deck<T>>> for Deck<T> {
-    fn from(vec: Vec<Deck<T>>) -> Self {
-        let mut out = Deck::with_capacity(vec.iter().map(|d| d.len()).sum());
-        for deck in vec {
+/// Gather a Vec of several Decks into a single deck. The result keeps the RNG of the first deck, or has its RNG
+/// seeded from entropy if there are none.
+impl<T, R: RngCore + SeedableRng> From<Vec<Deck<T, R>>> for Deck<T, R> {
+    fn from(vec: Vec<Deck<T, R>>) -> Self {
+        let capacity: usize = vec.iter().map(|d| d.len()).sum();
+        let mut decks = vec.into_iter();
+        let mut out = match decks.next() {
+            Some(first) => first,
+            None => return Deck::with_rng(VecDeque::new(), R::from_entropy()),
+        };
+        out.cards.reserve(capacity - out.len());
+        for deck in decks {
             out.extend(deck)
         }
         out
     }
 }
 
-/// Gather an Iterator of several Decks into a single deck.
-impl<T> FromIterator<Deck<T>> for Deck<T> {
-    fn from_iter<I: IntoIterator<Item = Deck<T>>>(iter: I) -> Self {
-        let mut out = Deck::empty();
-        for deck in iter.into_iter() {
+/// Gather an Iterator of several Decks into a single deck. The result keeps the RNG of the first deck, or has
+/// its RNG seeded from entropy if there are none.
+impl<T, R: RngCore + SeedableRng> FromIterator<Deck<T, R>> for Deck<T, R> {
+    fn from_iter<I: IntoIterator<Item = Deck<T, R>>>(iter: I) -> Self {
+        let mut decks = iter.into_iter();
+        let mut out = match decks.next() {
+            Some(first) => first,
+            None => return Deck::with_rng(VecDeque::new(), R::from_entropy()),
+        };
+        for deck in decks {
             out.extend(deck)
         }
         out
     }
 }
 
-impl<T> IntoIterator for Deck<T> {
+impl<T, R: RngCore + SeedableRng> IntoIterator for Deck<T, R> {
     type Item = T;
 
     type IntoIter = std::collections::vec_deque::IntoIter<Self::Item>;
@@ -389,6 +552,106 @@ mod test_deck {
         assert_eq!(deck.cards, [1, 2, 0]);
     }
 
+    #[test]
+    fn children_reproducible() {
+        // Decks made from a seeded deck shuffle the same way every time
+        let run = || {
+            let mut deck = Deck::from_iter(0..52);
+            deck.set_seed_u64(314159);
+            let mut bottom = deck.split_off_random();
+            bottom.shuffle();
+            let mut hands = deck.deal(3, 5).unwrap();
+            for hand in hands.iter_mut() {
+                hand.shuffle();
+            }
+            deck.shuffle();
+            (deck, bottom, hands)
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn other_rng() {
+        let mut deck = Deck::with_rng(
+            vec![0, 1, 2, 3, 4, 5],
+            rand::rngs::StdRng::seed_from_u64(314159),
+        );
+        deck.riffle();
+        deck.sort();
+        assert_eq!(deck.cards, [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn errors() {
+        let mut deck = Deck::from([1, 2, 3]);
+        assert_eq!(
+            deck.swap(0, 3),
+            Err(DeckError::IndexOutOfBounds { index: 3, len: 3 })
+        );
+        assert_eq!(
+            deck.try_place_nth(4, 100),
+            Err(DeckError::IndexOutOfBounds { index: 4, len: 3 })
+        );
+        assert!(deck.try_split_off_nth(4).is_err());
+        assert!(deck.try_cut_nth(4).is_err());
+        assert_eq!(deck.cards, [1, 2, 3]);
+        let (deck, e) = deck.try_split_nth(4).unwrap_err();
+        assert_eq!(e, DeckError::IndexOutOfBounds { index: 4, len: 3 });
+        assert_eq!(deck.cards, [1, 2, 3]);
+
+        let mut empty = Deck::<i32>::empty();
+        assert_eq!(empty.try_draw_random(), Err(DeckError::EmptyDeck));
+        assert_eq!(empty.try_cut_random(), Err(DeckError::EmptyDeck));
+        assert_eq!(empty.try_place_random(1), Err(DeckError::EmptyDeck));
+        let (empty, e) = empty.try_split_random().unwrap_err();
+        assert_eq!(e, DeckError::EmptyDeck);
+        assert!(empty.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut deck = Deck::from_iter(0..52);
+        deck.set_seed_u64(314159);
+        deck.riffle();
+        let mut restored: Deck<i32> =
+            serde_json::from_str(&serde_json::to_string(&deck).unwrap()).unwrap();
+        assert_eq!(deck, restored);
+        deck.shuffle();
+        restored.shuffle();
+        assert_eq!(deck.cards, restored.cards);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn rng_state() {
+        // This relies on how rand_xoshiro serializes its private state, distinct bytes catch any change to
+        // the order of the words or bytes
+        let seed: [u8; 32] = std::array::from_fn(|i| i as u8);
+        let mut deck = Deck::from_iter(0..52);
+        deck.set_seed(seed);
+        assert_eq!(deck.rng_state(), seed);
+
+        deck.riffle();
+        let state = deck.rng_state();
+        let cards = deck.cards.clone();
+        deck.shuffle();
+        deck.overhand(0.3);
+        let expected = deck.cards.clone();
+
+        deck.cards = cards;
+        deck.restore_rng_state(state);
+        deck.shuffle();
+        deck.overhand(0.3);
+        assert_eq!(deck.cards, expected);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Deck::from_iter(0..3).to_string(), "[0, 1, 2]");
+        assert_eq!(Deck::<i32>::empty().to_string(), "[]");
+    }
+
     #[test]
     fn reverse() {
         let mut deck = Deck::from_iter(0..=9);
@@ -0,0 +1,142 @@
+use rand::{RngCore, SeedableRng};
+use rand_xoshiro::Xoshiro256PlusPlus;
+use std::collections::VecDeque;
+
+use crate::deck::Deck;
+
+/// How the discard pile is turned into a new draw pile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReshufflePolicy {
+    /// Perform a Fisher-Yates shuffle.
+    FisherYates,
+    /// Perform the given number of riffle shuffles.
+    Riffles(usize),
+    /// Turn the discard pile over without shuffling it.
+    None,
+}
+
+/// A draw pile and a discard pile. When the draw pile runs out the discard pile is shuffled to make a new one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrawDiscard<T, R: RngCore + SeedableRng = Xoshiro256PlusPlus> {
+    /// The draw pile. Its RNG is used for every reshuffle.
+    pub draw_pile: Deck<T, R>,
+    /// The discard pile, the most recently discarded card is on top.
+    pub discard_pile: Deck<T, R>,
+    pub policy: ReshufflePolicy,
+    /// If true the top card of the discard pile stays behind when the rest is reshuffled.
+    pub keep_top_discard: bool,
+}
+
+impl<T, R: RngCore + SeedableRng> DrawDiscard<T, R> {
+    /// Use the deck as the draw pile with an empty discard pile whose RNG is seeded from the draw pile's RNG.
+    pub fn new(mut draw_pile: Deck<T, R>, policy: ReshufflePolicy) -> DrawDiscard<T, R> {
+        DrawDiscard {
+            discard_pile: draw_pile.child(VecDeque::new()),
+            draw_pile,
+            policy,
+            keep_top_discard: false,
+        }
+    }
+
+    /// Draw the top card of the draw pile, reshuffling the discard pile into a new draw pile first if needed.
+    /// The bool is true if a reshuffle happened. The card is None only if there were no cards to reshuffle.
+    pub fn draw(&mut self) -> (Option<T>, bool) {
+        if self.draw_pile.is_empty() {
+            let reshuffled = self.reshuffle();
+            (self.draw_pile.draw(), reshuffled)
+        } else {
+            (self.draw_pile.draw(), false)
+        }
+    }
+
+    /// Place a card on top of the discard pile.
+    pub fn discard(&mut self, card: T) {
+        self.discard_pile.place_top(card)
+    }
+
+    /// A reference to the top card of the discard pile.
+    pub fn top_discard(&self) -> Option<&T> {
+        self.discard_pile.top()
+    }
+
+    /// Take the top card of the discard pile.
+    pub fn draw_discard(&mut self) -> Option<T> {
+        self.discard_pile.draw()
+    }
+
+    /// Move the discard pile, except its top card if that is being kept, below the draw pile and shuffle the
+    /// draw pile according to the policy. Returns false if there were no cards to move.
+    pub fn reshuffle(&mut self) -> bool {
+        let kept = match self.keep_top_discard {
+            true => self.discard_pile.draw(),
+            false => None,
+        };
+        let reshuffled = !self.discard_pile.is_empty();
+        if reshuffled {
+            let mut cards = std::mem::take(&mut self.discard_pile.cards);
+            match self.policy {
+                ReshufflePolicy::FisherYates => {
+                    self.draw_pile.cards.append(&mut cards);
+                    self.draw_pile.shuffle();
+                }
+                ReshufflePolicy::Riffles(n) => {
+                    self.draw_pile.cards.append(&mut cards);
+                    for _ in 0..n {
+                        self.draw_pile.riffle();
+                    }
+                }
+                ReshufflePolicy::None => {
+                    cards.make_contiguous().reverse();
+                    self.draw_pile.cards.append(&mut cards);
+                }
+            }
+        }
+        if let Some(card) = kept {
+            self.discard_pile.place_top(card);
+        }
+        reshuffled
+    }
+}
+
+#[cfg(test)]
+mod test_draw_discard {
+    use super::*;
+
+    #[test]
+    fn reshuffle_on_empty() {
+        let mut deck = Deck::from_iter(0..10);
+        deck.set_seed_u64(314159);
+        let mut piles = DrawDiscard::new(deck, ReshufflePolicy::FisherYates);
+        piles.keep_top_discard = true;
+        for _ in 0..10 {
+            let (card, reshuffled) = piles.draw();
+            assert!(!reshuffled);
+            piles.discard(card.unwrap());
+        }
+        assert_eq!(piles.top_discard(), Some(&9));
+
+        let (card, reshuffled) = piles.draw();
+        assert!(reshuffled);
+        assert!(card.is_some());
+        assert_eq!(piles.draw_pile.len(), 8);
+        assert_eq!(piles.discard_pile.cards, [9]);
+    }
+
+    #[test]
+    fn turn_over() {
+        // Turning over the discard pile puts the first card discarded on top
+        let mut piles = DrawDiscard::new(Deck::from_iter(0..5), ReshufflePolicy::None);
+        for _ in 0..5 {
+            let card = piles.draw().0.unwrap();
+            piles.discard(card);
+        }
+        assert_eq!(piles.draw(), (Some(0), true));
+        assert_eq!(piles.draw_pile.cards, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn nothing_to_reshuffle() {
+        let mut piles = DrawDiscard::new(Deck::<i32>::empty(), ReshufflePolicy::Riffles(7));
+        assert_eq!(piles.draw(), (None, false));
+    }
+}
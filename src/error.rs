@@ -0,0 +1,71 @@
+use std::fmt::Display;
+
+/// Errors that can occur when manipulating a Deck.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeckError {
+    /// An index was used that is not a valid position in the deck.
+    IndexOutOfBounds { index: usize, len: usize },
+    /// The operation requires at least one card in the deck.
+    EmptyDeck,
+    /// A probability was given that is not between 0 and 1.
+    InvalidProbability(f64),
+    /// A pile shuffle was requested with no piles.
+    ZeroPiles,
+    /// More cards were needed than the deck contains.
+    NotEnoughCards { needed: usize, len: usize },
+    /// A permutation did not contain every position exactly once or could not be parsed.
+    InvalidPermutation,
+    /// Two things that must be the same length were not.
+    LengthMismatch { expected: usize, len: usize },
+    /// The parameters given for a distribution do not describe a valid distribution.
+    InvalidDistribution,
+    /// No sequence of the requested operations reaches the requested arrangement.
+    Unreachable,
+    /// At least one observation is needed.
+    NoObservations,
+    /// A range of positions starts after it ends.
+    InvalidRange { start: usize, end: usize },
+    /// A deal was requested for more players than the limit.
+    TooManyPlayers { players: usize, max: usize },
+}
+
+impl Display for DeckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckError::IndexOutOfBounds { index, len } => {
+                write!(
+                    f,
+                    "index {} is out of bounds for a deck of {} cards",
+                    index, len
+                )
+            }
+            DeckError::EmptyDeck => write!(f, "the deck is empty"),
+            DeckError::InvalidProbability(p) => {
+                write!(f, "{} is not a valid probability", p)
+            }
+            DeckError::ZeroPiles => write!(f, "at least one pile is required"),
+            DeckError::NotEnoughCards { needed, len } => {
+                write!(
+                    f,
+                    "{} cards are needed but the deck has only {}",
+                    needed, len
+                )
+            }
+            DeckError::InvalidPermutation => write!(f, "not a valid permutation"),
+            DeckError::LengthMismatch { expected, len } => {
+                write!(f, "expected a length of {} but found {}", expected, len)
+            }
+            DeckError::InvalidDistribution => write!(f, "not a valid distribution"),
+            DeckError::Unreachable => write!(f, "the requested arrangement cannot be reached"),
+            DeckError::NoObservations => write!(f, "at least one observation is required"),
+            DeckError::InvalidRange { start, end } => {
+                write!(f, "range starts at {} but ends at {}", start, end)
+            }
+            DeckError::TooManyPlayers { players, max } => {
+                write!(f, "{} players is more than the limit of {}", players, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DeckError {}
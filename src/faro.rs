@@ -0,0 +1,179 @@
+use rand::{RngCore, SeedableRng};
+use std::collections::VecDeque;
+
+use crate::{deck::Deck, error::DeckError};
+
+/// The position that the card at position p moves to after a single faro of a deck with len cards. This
+/// matches .faro() where the extra card of an odd deck is in the lower half and ends up on the bottom.
+pub fn faro_destination(len: usize, out: bool, p: usize) -> usize {
+    let half = len / 2;
+    match (p < half, out) {
+        (true, true) => 2 * p,
+        (true, false) => 2 * p + 1,
+        (false, _) if p == 2 * half => p,
+        (false, true) => 2 * (p - half) + 1,
+        (false, false) => 2 * (p - half),
+    }
+}
+
+/// The sequence of faros that moves the top card of a deck with len cards to position k, using Elmsley's
+/// binary method: reading k in binary from the highest bit a 1 is an in shuffle and a 0 is an out shuffle.
+/// Each entry is the out argument to give .faro(). Returns an error if k is not a position in the deck or the
+/// top card can never reach it, which is only the bottom card of an odd deck.
+pub fn top_to_position(len: usize, k: usize) -> Result<Vec<bool>, DeckError> {
+    if k >= len {
+        return Err(DeckError::IndexOutOfBounds { index: k, len });
+    }
+    if !len.is_multiple_of(2) && k == len - 1 {
+        return Err(DeckError::Unreachable);
+    }
+    let bits = usize::BITS - k.leading_zeros();
+    Ok((0..bits).rev().map(|b| (k >> b) & 1 == 0).collect())
+}
+
+/// The shortest sequence of faros that brings the card at position k to the top of a deck with len cards.
+/// Each entry is the out argument to give .faro(). Returns an error if k is not a position in the deck or the
+/// card can never reach the top.
+pub fn position_to_top(len: usize, k: usize) -> Result<Vec<bool>, DeckError> {
+    if k >= len {
+        return Err(DeckError::IndexOutOfBounds { index: k, len });
+    }
+    // Breadth first search over positions, remembering how each one was first reached
+    let mut prev: Vec<Option<(usize, bool)>> = vec![None; len];
+    let mut queue = VecDeque::from([k]);
+    while let Some(p) = queue.pop_front() {
+        if p == 0 {
+            break;
+        }
+        for out in [true, false] {
+            let q = faro_destination(len, out, p);
+            if q != k && prev[q].is_none() {
+                prev[q] = Some((p, out));
+                queue.push_back(q);
+            }
+        }
+    }
+
+    let mut seq = Vec::new();
+    let mut p = 0;
+    while p != k {
+        let (from, out) = prev[p].ok_or(DeckError::Unreachable)?;
+        seq.push(out);
+        p = from;
+    }
+    seq.reverse();
+    Ok(seq)
+}
+
+/// The number of faros of the same kind needed to restore a deck with len cards to its original order. This is
+/// the multiplicative order of 2 modulo a number determined by the size of the deck and kind of faro.
+pub fn faro_order(len: usize, out: bool) -> usize {
+    // An out faro of an even deck doubles positions modulo len - 1 and an in faro doubles one more than the
+    // position modulo len + 1. For an odd deck the bottom card never moves so an out faro acts on the rest of
+    // the deck, while an in faro doubles one more than the position modulo len.
+    let modulus = match (len.is_multiple_of(2), out) {
+        (true, true) => len.saturating_sub(1),
+        (true, false) => len + 1,
+        (false, true) => len.saturating_sub(2),
+        (false, false) => len,
+    };
+    if modulus <= 1 {
+        return 1;
+    }
+    let mut order = 1;
+    let mut x = 2 % modulus;
+    while x != 1 {
+        x = (x * 2) % modulus;
+        order += 1;
+    }
+    order
+}
+
+impl<T, R: RngCore + SeedableRng> Deck<T, R> {
+    /// Perform a sequence of faros where each entry is the out argument to give .faro().
+    pub fn faros(&mut self, sequence: &[bool]) {
+        for &out in sequence {
+            self.faro(out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_faro {
+    use super::*;
+
+    #[test]
+    fn destination() {
+        for len in 0..20 {
+            for out in [true, false] {
+                let mut deck = Deck::from_iter(0..len);
+                deck.faro(out);
+                for (pos, &card) in deck.iter().enumerate() {
+                    assert_eq!(faro_destination(len, out, card), pos);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn top_to_position() {
+        // Elmsley's example, moving the top card to position 5 is in, out, in
+        assert_eq!(super::top_to_position(52, 5), Ok(vec![false, true, false]));
+        for len in 1..40 {
+            for k in 0..len {
+                match super::top_to_position(len, k) {
+                    Ok(seq) => {
+                        let mut deck = Deck::from_iter(0..len);
+                        deck.faros(&seq);
+                        assert_eq!(deck.cards[k], 0);
+                    }
+                    Err(e) => {
+                        assert_eq!(e, DeckError::Unreachable);
+                        assert_eq!((len % 2, k), (1, len - 1));
+                    }
+                }
+            }
+        }
+        assert!(super::top_to_position(52, 52).is_err());
+    }
+
+    #[test]
+    fn position_to_top() {
+        for len in 1..40 {
+            for k in 0..len {
+                match super::position_to_top(len, k) {
+                    Ok(seq) => {
+                        let mut deck = Deck::from_iter(0..len);
+                        deck.faros(&seq);
+                        assert_eq!(deck.cards[0], k);
+                    }
+                    Err(e) => {
+                        assert_eq!(e, DeckError::Unreachable);
+                        assert_eq!((len % 2, k), (1, len - 1));
+                    }
+                }
+            }
+        }
+        assert!(super::position_to_top(52, 52).is_err());
+    }
+
+    #[test]
+    fn faro_order() {
+        assert_eq!(super::faro_order(52, true), 8);
+        assert_eq!(super::faro_order(52, false), 52);
+        for len in 0..60 {
+            for out in [true, false] {
+                let mut deck = Deck::from_iter(0..len);
+                let mut count = 0;
+                loop {
+                    deck.faro(out);
+                    count += 1;
+                    if deck.iter().copied().eq(0..len) {
+                        break;
+                    }
+                }
+                assert_eq!(super::faro_order(len, out), count, "{} {}", len, out);
+            }
+        }
+    }
+}
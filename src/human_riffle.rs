@@ -0,0 +1,315 @@
+use rand::{distributions::WeightedIndex, Rng, RngCore, SeedableRng};
+use rand_distr::{Binomial, Distribution, Geometric, Normal};
+
+use crate::{deck::Deck, error::DeckError};
+
+/// How the position of the cut is chosen for a riffle.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CutDistribution {
+    /// A binomial distribution, as used by .riffle()
+    Binomial,
+    /// A normal distribution centered on the middle of the deck with the given standard deviation in cards.
+    Normal { sd: f64 },
+    /// A histogram of weights over equal width bins of the possible cut positions, from top to bottom. The cut
+    /// is placed uniformly within the chosen bin.
+    Empirical(Vec<f64>),
+}
+
+/// A model of a riffle shuffle performed by a person. The default is equivalent to the Gilbert-Shannon-Reeds
+/// model used by .riffle() and each field moves it toward how real hands behave.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HumanRiffle {
+    /// Where the deck is cut.
+    pub cut: CutDistribution,
+    /// Cards drop in clumps whose size is one plus a geometric distribution with this probability. A value of
+    /// 1.0 drops one card at a time.
+    pub clump_p: f64,
+    /// How much more readily the right hand drops cards than the left. A value of 1.0 is unbiased.
+    pub right_bias: f64,
+    /// Probability that two cards next to each other in a packet are stuck together and drop as one.
+    pub sticky: f64,
+}
+
+impl Default for HumanRiffle {
+    fn default() -> Self {
+        HumanRiffle {
+            cut: CutDistribution::Binomial,
+            clump_p: 1.0,
+            right_bias: 1.0,
+            sticky: 0.0,
+        }
+    }
+}
+
+impl HumanRiffle {
+    /// An illustrative model of a practiced dealer. The cut is close to the middle and most cards drop singly.
+    pub fn professional() -> HumanRiffle {
+        HumanRiffle {
+            cut: CutDistribution::Normal { sd: 1.5 },
+            clump_p: 0.8,
+            right_bias: 1.0,
+            sticky: 0.0,
+        }
+    }
+
+    /// An illustrative model of an amateur. The cut wanders, cards drop in larger clumps, one hand is
+    /// favored, and some cards stick together.
+    pub fn amateur() -> HumanRiffle {
+        HumanRiffle {
+            cut: CutDistribution::Normal { sd: 4.0 },
+            clump_p: 0.5,
+            right_bias: 1.3,
+            sticky: 0.05,
+        }
+    }
+
+    fn validate(&self) -> Result<(), DeckError> {
+        for p in [self.clump_p, self.sticky] {
+            if !(0.0..=1.0).contains(&p) {
+                return Err(DeckError::InvalidProbability(p));
+            }
+        }
+        if self.clump_p == 0.0 || !(self.right_bias > 0.0 && self.right_bias.is_finite()) {
+            return Err(DeckError::InvalidDistribution);
+        }
+        match &self.cut {
+            CutDistribution::Normal { sd } if !(*sd >= 0.0 && sd.is_finite()) => {
+                Err(DeckError::InvalidDistribution)
+            }
+            CutDistribution::Empirical(weights) if WeightedIndex::new(weights).is_err() => {
+                Err(DeckError::InvalidDistribution)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Choose the number of cards in the top packet.
+    fn cut_position<R: Rng>(&self, len: usize, rng: &mut R) -> usize {
+        match &self.cut {
+            CutDistribution::Binomial => {
+                let bin = Binomial::new(len as u64, 0.5).unwrap();
+                usize::try_from(bin.sample(rng)).unwrap()
+            }
+            CutDistribution::Normal { sd } => {
+                let norm = Normal::new(len as f64 / 2.0, *sd).unwrap();
+                norm.sample(rng).round().clamp(0.0, len as f64) as usize
+            }
+            CutDistribution::Empirical(weights) => {
+                // The len + 1 possible cut positions are split into bins that don't overlap so only the last
+                // bin can cut at len. A bin is empty only if there are more bins than positions.
+                let bin = WeightedIndex::new(weights).unwrap().sample(rng);
+                let lo = bin * (len + 1) / weights.len();
+                let hi = (bin + 1) * (len + 1) / weights.len();
+                match lo < hi {
+                    true => rng.gen_range(lo..hi),
+                    false => lo.min(len),
+                }
+            }
+        }
+    }
+}
+
+impl<T, R: RngCore + SeedableRng> Deck<T, R> {
+    /// Perform a single riffle shuffle following a model of human riffling. Returns an error if the model has
+    /// invalid parameters.
+    pub fn riffle_model(&mut self, model: &HumanRiffle) -> Result<(), DeckError> {
+        model.validate()?;
+        let n = model.cut_position(self.len(), &mut self.rng);
+        let right = self.split_off_packet(n);
+        self.riffle_with_model(right, model)
+    }
+
+    /// Riffle shuffle another Deck into this one, consuming the other Deck, following a model of human
+    /// riffling. This deck is held in the left hand. Returns an error if the model has invalid parameters.
+    pub fn riffle_with_model(
+        &mut self,
+        right: Deck<T, R>,
+        model: &HumanRiffle,
+    ) -> Result<(), DeckError> {
+        model.validate()?;
+        let clump = Geometric::new(model.clump_p).unwrap();
+
+        // Decide which neighboring cards in each packet are stuck to the card after them
+        let (l_len, r_len) = (self.len(), right.len());
+        let mut stuck = |len: usize| -> Vec<bool> {
+            match model.sticky > 0.0 {
+                true => (0..len).map(|_| self.rng.gen_bool(model.sticky)).collect(),
+                false => Vec::new(),
+            }
+        };
+        // Flags are popped from the back as cards are taken from the front
+        let mut l_stuck = stuck(l_len);
+        let mut r_stuck = stuck(r_len);
+        l_stuck.reverse();
+        r_stuck.reverse();
+
+        let mut left = std::mem::take(&mut self.cards);
+        let mut right = right.cards;
+        self.cards.reserve_exact(left.len() + right.len());
+
+        while !left.is_empty() && !right.is_empty() {
+            let l = left.len() as f64;
+            let r = right.len() as f64 * model.right_bias;
+            let (packet, stuck) = match self.rng.gen_bool(r / (l + r)) {
+                true => (&mut right, &mut r_stuck),
+                false => (&mut left, &mut l_stuck),
+            };
+            let size = 1 + clump.sample(&mut self.rng) as usize;
+            let mut dropped = 0;
+            while let Some(card) = packet.pop_front() {
+                self.cards.push_back(card);
+                dropped += 1;
+                let is_stuck = stuck.pop().unwrap_or(false);
+                if dropped >= size && !is_stuck {
+                    break;
+                }
+            }
+        }
+        self.cards.append(&mut left);
+        self.cards.append(&mut right);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_human_riffle {
+    use super::*;
+
+    #[test]
+    fn models() {
+        for model in [
+            HumanRiffle::default(),
+            HumanRiffle::professional(),
+            HumanRiffle::amateur(),
+            HumanRiffle {
+                cut: CutDistribution::Empirical(vec![1.0, 4.0, 4.0, 1.0]),
+                ..Default::default()
+            },
+        ] {
+            let mut a = Deck::from_iter(0..52);
+            let mut b = Deck::from_iter(0..52);
+            a.set_seed_u64(314159);
+            b.set_seed_u64(314159);
+            for _ in 0..5 {
+                a.riffle_model(&model).unwrap();
+                b.riffle_model(&model).unwrap();
+            }
+            assert_eq!(a.cards, b.cards);
+            a.sort();
+            assert_eq!(a.cards, (0..52).collect::<Vec<_>>());
+        }
+    }
+
+    // Average number of times consecutive cards come from different halves when riffling 0..26 with 26..52.
+    fn switches(model: &HumanRiffle) -> f64 {
+        let mut source = Deck::<u8>::empty();
+        source.set_seed_u64(314159);
+        let trials = 2000;
+        let mut total = 0;
+        for _ in 0..trials {
+            // Every trial continues the same seeded stream
+            let mut deck = Deck::from_iter(0..26);
+            std::mem::swap(&mut deck.rng, &mut source.rng);
+            deck.riffle_with_model(Deck::from_iter(26..52), model)
+                .unwrap();
+            std::mem::swap(&mut deck.rng, &mut source.rng);
+            total += deck
+                .cards
+                .iter()
+                .zip(deck.cards.iter().skip(1))
+                .filter(|(a, b)| (**a < 26) != (**b < 26))
+                .count();
+        }
+        total as f64 / trials as f64
+    }
+
+    #[test]
+    fn parameters_have_effect() {
+        let base = switches(&HumanRiffle::default());
+        let clumpy = switches(&HumanRiffle {
+            clump_p: 0.3,
+            ..Default::default()
+        });
+        let sticky = switches(&HumanRiffle {
+            sticky: 0.5,
+            ..Default::default()
+        });
+        // Cards dropping together means fewer changes between the halves
+        assert!(clumpy < 0.6 * base, "{} {}", clumpy, base);
+        assert!(sticky < 0.8 * base, "{} {}", sticky, base);
+
+        // With a biased right hand the right packet runs out first so the bottom card is usually from the left
+        let model = HumanRiffle {
+            right_bias: 3.0,
+            ..Default::default()
+        };
+        let mut source = Deck::<u8>::empty();
+        source.set_seed_u64(314159);
+        let mut left_bottom = 0;
+        for _ in 0..1000 {
+            let mut left = Deck::from_iter(0..26);
+            std::mem::swap(&mut left.rng, &mut source.rng);
+            left.riffle_with_model(Deck::from_iter(26..52), &model)
+                .unwrap();
+            std::mem::swap(&mut left.rng, &mut source.rng);
+            if *left.bottom().unwrap() < 26 {
+                left_bottom += 1;
+            }
+        }
+        assert!(left_bottom > 900, "{}", left_bottom);
+    }
+
+    #[test]
+    fn empirical_bins() {
+        // Two equal bins over a deck of 3 cards split the cut positions 0, 1 | 2, 3 with nothing counted twice
+        let model = HumanRiffle {
+            cut: CutDistribution::Empirical(vec![1.0, 1.0]),
+            ..Default::default()
+        };
+        let mut deck = Deck::<u8>::empty();
+        deck.set_seed_u64(314159);
+        let mut counts = [0; 4];
+        for _ in 0..4000 {
+            counts[model.cut_position(3, &mut deck.rng)] += 1;
+        }
+        for c in counts {
+            assert!((900..1100).contains(&c), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn fully_sticky() {
+        // When every card sticks the whole packet drops at once
+        let mut deck = Deck::from_iter(0..10);
+        let model = HumanRiffle {
+            sticky: 1.0,
+            ..Default::default()
+        };
+        deck.riffle_with_model(Deck::from_iter(10..20), &model)
+            .unwrap();
+        assert!(deck.cards[0] == 0 || deck.cards[0] == 10);
+        assert!(deck.cards[9] == 9 || deck.cards[9] == 19);
+    }
+
+    #[test]
+    fn invalid() {
+        let mut deck = Deck::from_iter(0..10);
+        let model = HumanRiffle {
+            sticky: 2.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            deck.riffle_model(&model),
+            Err(DeckError::InvalidProbability(2.0))
+        );
+        let model = HumanRiffle {
+            cut: CutDistribution::Empirical(vec![0.0, 0.0]),
+            ..Default::default()
+        };
+        assert_eq!(
+            deck.riffle_model(&model),
+            Err(DeckError::InvalidDistribution)
+        );
+        assert_eq!(deck.cards, (0..10).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::{
+    error::DeckError,
+    permutation::Permutation,
+    special::{ln_gamma, normal_isf},
+};
+
+// The largest number of riffles considered. By this point any deck of reasonable size is indistinguishable
+// from uniform.
+const MAX_RIFFLES: u32 = 40;
+
+/// A maximum likelihood estimate of the number of riffle shuffles with a likelihood ratio confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiffleEstimate {
+    /// The most likely number of riffles.
+    pub k: u32,
+    /// The smallest number of riffles in the confidence interval.
+    pub lower: u32,
+    /// The largest number of riffles in the confidence interval. None if the observations cannot be told apart
+    /// from any larger number of riffles, which happens once the decks are close to uniform.
+    pub upper: Option<u32>,
+}
+
+/// A maximum likelihood estimate of the probability of a packet break with a likelihood ratio confidence
+/// interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PacketEstimate {
+    /// The most likely probability.
+    pub p: f64,
+    /// Lower end of the confidence interval.
+    pub lower: f64,
+    /// Upper end of the confidence interval.
+    pub upper: f64,
+}
+
+// Check the observations and confidence level and return the number of cards.
+fn validate(observed: &[Permutation], confidence: f64) -> Result<usize, DeckError> {
+    if !(confidence > 0.0 && confidence < 1.0) {
+        return Err(DeckError::InvalidProbability(confidence));
+    }
+    let n = observed.first().ok_or(DeckError::NoObservations)?.len();
+    match observed.iter().find(|p| p.len() != n) {
+        Some(p) => Err(DeckError::LengthMismatch {
+            expected: n,
+            len: p.len(),
+        }),
+        None => Ok(n),
+    }
+}
+
+// Half the chi-square critical value with one degree of freedom. Parameters whose log likelihood is within
+// this of the maximum are in the confidence interval.
+fn log_likelihood_drop(confidence: f64) -> f64 {
+    normal_isf((1.0 - confidence) / 2.0).powi(2) / 2.0
+}
+
+/// Estimate how many times .riffle() was repeated to produce the observed shuffles, with a confidence interval
+/// of the given coverage such as 0.95. Returns an error for invalid observations or confidence.
+pub fn estimate_riffles(
+    observed: &[Permutation],
+    confidence: f64,
+) -> Result<RiffleEstimate, DeckError> {
+    let n = validate(observed, confidence)?;
+    let rising: Vec<usize> = observed.iter().map(|p| p.rising_sequences()).collect();
+
+    // The log probability of a permutation with r rising sequences after k riffles is
+    // ln C(2^k + n - r, n) - kn ln 2, written as a sum of ln_1p terms so it stays accurate for large k
+    let ln_n_factorial = ln_gamma(n as f64 + 1.0);
+    let log_likelihood = |k: u32| -> f64 {
+        let a = 2f64.powi(k as i32);
+        rising
+            .iter()
+            .map(|&r| {
+                (0..n)
+                    .map(|i| {
+                        let x = a + n as f64 - r as f64 - i as f64;
+                        match x > 0.0 {
+                            true => ((n as f64 - r as f64 - i as f64) / a).ln_1p(),
+                            false => f64::NEG_INFINITY,
+                        }
+                    })
+                    .sum::<f64>()
+                    - ln_n_factorial
+            })
+            .sum()
+    };
+
+    let ll: Vec<f64> = (0..=MAX_RIFFLES).map(log_likelihood).collect();
+    let k = (0..=MAX_RIFFLES)
+        .max_by(|&a, &b| ll[a as usize].total_cmp(&ll[b as usize]).then(b.cmp(&a)))
+        .unwrap();
+    let cutoff = ll[k as usize] - log_likelihood_drop(confidence);
+    let inside: Vec<u32> = (0..=MAX_RIFFLES)
+        .filter(|&j| ll[j as usize] >= cutoff)
+        .collect();
+    let upper = *inside.last().unwrap();
+    Ok(RiffleEstimate {
+        k,
+        lower: inside[0],
+        upper: (upper < MAX_RIFFLES).then_some(upper),
+    })
+}
+
+/// Estimate the probability p given to .overhand() that produced the observed shuffles, with a confidence
+/// interval of the given coverage. Also returns an error if an observation cannot be made by .overhand().
+pub fn estimate_overhand(
+    observed: &[Permutation],
+    confidence: f64,
+) -> Result<PacketEstimate, DeckError> {
+    // An overhand shuffle is the Premantle shuffle followed by turning the deck over
+    let flipped: Vec<Permutation> = observed
+        .iter()
+        .map(|p| Permutation::new_unchecked(p.as_slice().iter().rev().copied().collect()))
+        .collect();
+    estimate_premantle(&flipped, confidence)
+}
+
+/// Estimate the probability p given to .premantle() that produced the observed shuffles, with a confidence
+/// interval of the given coverage. Also returns an error if an observation cannot be made by .premantle().
+pub fn estimate_premantle(
+    observed: &[Permutation],
+    confidence: f64,
+) -> Result<PacketEstimate, DeckError> {
+    let n = validate(observed, confidence)?;
+    let counts = observed
+        .iter()
+        .map(break_counts)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Each of the n - 1 gaps between cards breaks independently with probability p
+    let gaps = n.saturating_sub(1) as i32;
+    let log_likelihood = |p: f64| -> f64 {
+        counts
+            .iter()
+            .map(|c| {
+                c.iter()
+                    .enumerate()
+                    .map(|(k, &ways)| ways * p.powi(k as i32) * (1.0 - p).powi(gaps - k as i32))
+                    .sum::<f64>()
+                    .ln()
+            })
+            .sum()
+    };
+
+    // Golden section search for the maximum
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut lo, mut hi) = (0.0, 1.0);
+    for _ in 0..100 {
+        let a = hi - ratio * (hi - lo);
+        let b = lo + ratio * (hi - lo);
+        match log_likelihood(a) < log_likelihood(b) {
+            true => lo = a,
+            false => hi = b,
+        }
+    }
+    let mut p = (lo + hi) / 2.0;
+    // The maximum may be at an end of the range
+    for end in [0.0, 1.0] {
+        if log_likelihood(end) >= log_likelihood(p) {
+            p = end;
+        }
+    }
+
+    let cutoff = log_likelihood(p) - log_likelihood_drop(confidence);
+    // Bisect for the point between inside and outside where the log likelihood crosses the cutoff
+    let crossing = |mut inside: f64, mut outside: f64| -> f64 {
+        if log_likelihood(outside) >= cutoff {
+            return outside;
+        }
+        for _ in 0..100 {
+            let mid = (inside + outside) / 2.0;
+            match log_likelihood(mid) >= cutoff {
+                true => inside = mid,
+                false => outside = mid,
+            }
+        }
+        inside
+    };
+    Ok(PacketEstimate {
+        p,
+        lower: crossing(p, 0.0),
+        upper: crossing(p, 1.0),
+    })
+}
+
+// The number of sets of packet breaks with each number of breaks that give the permutation under the Premantle
+// shuffle, which reverses every packet except the last.
+fn break_counts(perm: &Permutation) -> Result<Vec<f64>, DeckError> {
+    let s = perm.as_slice();
+    let n = s.len();
+    // ways[a][k] counts sets of k breaks that explain the cards above a with a packet starting at a
+    let mut ways = vec![vec![0.0; n.max(1)]; n.max(1)];
+    let mut out = vec![0.0; n.max(1)];
+    ways[0][0] = 1.0;
+    for a in 0..n {
+        if ways[a].iter().all(|&w| w == 0.0) {
+            continue;
+        }
+        // The last packet is left as it is
+        if (a..n).all(|i| s[i] == i) {
+            for (k, &w) in ways[a].iter().enumerate() {
+                out[k] += w;
+            }
+        }
+        // Any other packet is reversed so the card at a determines where the packet ends
+        let b = s[a] + 1;
+        if b > a && b < n && (a..b).all(|i| s[i] == a + b - 1 - i) {
+            for k in 0..n - 1 {
+                ways[b][k + 1] += ways[a][k];
+            }
+        }
+    }
+    if n > 0 && out.iter().all(|&w| w == 0.0) {
+        return Err(DeckError::Unreachable);
+    }
+    if n == 0 {
+        out[0] = 1.0;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test_inference {
+    use super::*;
+    use crate::permutation::observe;
+
+    #[test]
+    fn riffles() {
+        for k in [1, 3, 5] {
+            let observed = observe(52, 200, |d| {
+                for _ in 0..k {
+                    d.riffle();
+                }
+            });
+            let est = estimate_riffles(&observed, 0.95).unwrap();
+            assert_eq!(est.k, k);
+            assert!(est.lower <= k && est.upper.unwrap() >= k);
+        }
+
+        // A uniform shuffle looks like any large number of riffles
+        let est = estimate_riffles(&observe(52, 50, |d| d.shuffle()), 0.95).unwrap();
+        assert!(est.k >= 7);
+        assert_eq!(est.upper, None);
+    }
+
+    #[test]
+    fn overhand() {
+        for p in [0.1, 0.3, 0.7] {
+            let observed = observe(52, 200, |d| d.overhand(p));
+            let est = estimate_overhand(&observed, 0.95).unwrap();
+            assert!((est.p - p).abs() < 0.02, "{:?}", est);
+            assert!(est.lower < p && p < est.upper, "{:?}", est);
+
+            let observed = observe(52, 200, |d| d.premantle(p));
+            let est = estimate_premantle(&observed, 0.95).unwrap();
+            assert!((est.p - p).abs() < 0.02, "{:?}", est);
+            assert!(est.lower < p && p < est.upper, "{:?}", est);
+        }
+    }
+
+    #[test]
+    fn break_counts() {
+        // Only the packets [0], [1, 2] and [3] give 0 2 1 3
+        let perm = Permutation::new(vec![0, 2, 1, 3]).unwrap();
+        assert_eq!(super::break_counts(&perm), Ok(vec![0.0, 0.0, 1.0, 0.0]));
+        // Breaking after the top card, after every card, or not at all leave the deck unchanged
+        let perm = Permutation::identity(3);
+        assert_eq!(super::break_counts(&perm), Ok(vec![1.0, 1.0, 1.0]));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(estimate_riffles(&[], 0.95), Err(DeckError::NoObservations));
+        let mixed = [Permutation::identity(3), Permutation::identity(4)];
+        assert_eq!(
+            estimate_overhand(&mixed, 0.95),
+            Err(DeckError::LengthMismatch {
+                expected: 3,
+                len: 4
+            })
+        );
+        assert_eq!(
+            estimate_riffles(&[Permutation::identity(3)], 1.0),
+            Err(DeckError::InvalidProbability(1.0))
+        );
+        // A single swap in the middle of the deck can't be made by reversing packets
+        let swap = Permutation::new(vec![0, 2, 1, 3]).unwrap();
+        assert_eq!(
+            estimate_overhand(&[swap], 0.95),
+            Err(DeckError::Unreachable)
+        );
+    }
+}
@@ -1,4 +1,21 @@
+pub mod analysis;
+pub mod batch;
+pub mod cards;
+pub mod dealing;
 pub mod deck;
+pub mod draw_discard;
+pub mod error;
+pub mod faro;
+pub mod human_riffle;
+pub mod inference;
+pub mod permutation;
+pub mod randomness;
+pub mod shoe;
 pub mod shuffling;
+#[cfg(feature = "rayon")]
+pub mod simulate;
+mod special;
 mod speed_tests;
-pub use shuffling::*;
+pub mod statistics;
+pub use deck::Deck;
+pub use error::DeckError;
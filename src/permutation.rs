@@ -0,0 +1,506 @@
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive};
+use rand::{RngCore, SeedableRng};
+use std::{fmt::Display, str::FromStr};
+
+use crate::{deck::Deck, error::DeckError};
+
+/// A permutation of n positions, stored as a Vec where entry i is the position that the card now at position
+/// i came from. In cycle notation (0 1 2) moves the card at position 1 to position 0, the card at position 2 to
+/// position 1, and the card at position 0 to position 2, just like Deck::cycle.
+///
+/// An observed shuffle is the permutation taking the starting order of a deck to its order after shuffling, as
+/// made by Deck::record(). The analysis, inference, and randomness functions all take observations in this form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "Vec<usize>"))]
+pub struct Permutation(Vec<usize>);
+
+impl Permutation {
+    /// Create a permutation, returns an error if the Vec does not contain each of 0..n exactly once.
+    pub fn new(vec: Vec<usize>) -> Result<Permutation, DeckError> {
+        let mut seen = vec![false; vec.len()];
+        for &i in vec.iter() {
+            if i >= vec.len() || seen[i] {
+                return Err(DeckError::InvalidPermutation);
+            }
+            seen[i] = true;
+        }
+        Ok(Permutation(vec))
+    }
+
+    // For permutations constructed by the crate that are known to be valid.
+    pub(crate) fn new_unchecked(vec: Vec<usize>) -> Permutation {
+        Permutation(vec)
+    }
+
+    /// The permutation of n positions that changes nothing.
+    pub fn identity(n: usize) -> Permutation {
+        Permutation((0..n).collect())
+    }
+
+    /// Create a permutation of n positions from a list of cycles. Returns an error if a position is out of
+    /// bounds or appears more than once.
+    pub fn from_cycles<C: AsRef<[usize]>>(
+        n: usize,
+        cycles: &[C],
+    ) -> Result<Permutation, DeckError> {
+        let mut vec: Vec<usize> = (0..n).collect();
+        let mut seen = vec![false; n];
+        for cycle in cycles {
+            let cycle = cycle.as_ref();
+            for &i in cycle {
+                if i >= n || seen[i] {
+                    return Err(DeckError::InvalidPermutation);
+                }
+                seen[i] = true;
+            }
+            for (k, &i) in cycle.iter().enumerate() {
+                vec[i] = cycle[(k + 1) % cycle.len()];
+            }
+        }
+        Ok(Permutation(vec))
+    }
+
+    /// Parse cycle notation such as "(0 1 2)(3 4)" as a permutation of n positions. Commas may be used
+    /// between positions.
+    pub fn from_cycle_notation(s: &str, n: usize) -> Result<Permutation, DeckError> {
+        Permutation::from_cycles(n, &parse_cycles(s)?)
+    }
+
+    /// Number of positions permuted.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// True if this is a permutation of no positions.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The underlying slice.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.0
+    }
+
+    /// True if the permutation changes nothing.
+    pub fn is_identity(&self) -> bool {
+        self.0.iter().enumerate().all(|(i, &p)| i == p)
+    }
+
+    /// The permutation that results from applying this permutation and then the other. Returns an error if
+    /// they are not the same length.
+    pub fn then(&self, other: &Permutation) -> Result<Permutation, DeckError> {
+        if self.len() != other.len() {
+            return Err(DeckError::LengthMismatch {
+                expected: self.len(),
+                len: other.len(),
+            });
+        }
+        Ok(Permutation(other.0.iter().map(|&i| self.0[i]).collect()))
+    }
+
+    /// The permutation that undoes this one.
+    pub fn inverse(&self) -> Permutation {
+        let mut vec = vec![0; self.len()];
+        for (i, &p) in self.0.iter().enumerate() {
+            vec[p] = i
+        }
+        Permutation(vec)
+    }
+
+    /// Apply the permutation k times, a negative k applies the inverse.
+    pub fn pow(&self, k: i64) -> Permutation {
+        let mut base = match k < 0 {
+            true => self.inverse(),
+            false => self.clone(),
+        };
+        let mut k = k.unsigned_abs();
+        let mut out = Permutation::identity(self.len());
+        while k > 0 {
+            if k & 1 == 1 {
+                out = out.then(&base).unwrap();
+            }
+            base = base.then(&base).unwrap();
+            k >>= 1;
+        }
+        out
+    }
+
+    /// Decompose the permutation into disjoint cycles, including fixed points as cycles of length one. Each
+    /// cycle starts with its smallest position and the cycles are ordered by their first position.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.len()];
+        let mut out = Vec::new();
+        for start in 0..self.len() {
+            if seen[start] {
+                continue;
+            }
+            let mut cycle = Vec::new();
+            let mut i = start;
+            while !seen[i] {
+                seen[i] = true;
+                cycle.push(i);
+                i = self.0[i];
+            }
+            out.push(cycle);
+        }
+        out
+    }
+
+    /// The number of times the permutation must be applied to return to the identity. This is the least
+    /// common multiple of the cycle lengths, which quickly becomes too large for any primitive integer.
+    pub fn order(&self) -> BigUint {
+        self.cycles().iter().fold(BigUint::one(), |acc, c| {
+            let len = c.len();
+            let rem = (&acc % len)
+                .to_usize()
+                .expect("the remainder is less than len");
+            let g = gcd(rem, len);
+            acc * (len / g)
+        })
+    }
+
+    /// The number of rising sequences, maximal runs of consecutive original positions that appear in increasing
+    /// order. A single riffle shuffle produces at most two.
+    pub fn rising_sequences(&self) -> usize {
+        let inv = self.inverse();
+        let descents = inv.0.windows(2).filter(|w| w[1] < w[0]).count();
+        match self.is_empty() {
+            true => 0,
+            false => descents + 1,
+        }
+    }
+
+    /// The number of positions i where the entry at i is greater than the entry at i + 1.
+    pub fn descents(&self) -> usize {
+        self.0.windows(2).filter(|w| w[1] < w[0]).count()
+    }
+
+    /// The number of pairs of positions whose entries are out of order.
+    pub fn inversions(&self) -> usize {
+        // Count the larger entries already seen with a Fenwick tree
+        let mut tree = vec![0; self.len() + 1];
+        let mut out = 0;
+        for (seen, &p) in self.0.iter().enumerate() {
+            let mut smaller = 0;
+            let mut i = p + 1;
+            while i > 0 {
+                smaller += tree[i];
+                i &= i - 1;
+            }
+            out += seen - smaller;
+            let mut i = p + 1;
+            while i < tree.len() {
+                tree[i] += 1;
+                i += i & i.wrapping_neg();
+            }
+        }
+        out
+    }
+
+    /// The number of positions left unchanged.
+    pub fn fixed_points(&self) -> usize {
+        self.0.iter().enumerate().filter(|(i, &p)| *i == p).count()
+    }
+
+    /// The length of the longest increasing subsequence of the entries.
+    pub fn longest_increasing_subsequence(&self) -> usize {
+        // Smallest possible last entry of an increasing subsequence of each length
+        let mut tails: Vec<usize> = Vec::new();
+        for &p in self.0.iter() {
+            let i = tails.partition_point(|&t| t < p);
+            match tails.get_mut(i) {
+                Some(t) => *t = p,
+                None => tails.push(p),
+            }
+        }
+        tails.len()
+    }
+
+    /// The lengths of the cycles, including fixed points, from longest to shortest.
+    pub fn cycle_type(&self) -> Vec<usize> {
+        let mut lens: Vec<usize> = self.cycles().iter().map(|c| c.len()).collect();
+        lens.sort_unstable_by(|a, b| b.cmp(a));
+        lens
+    }
+
+    /// The sign of the permutation, 1 if it is made of an even number of transpositions and -1 otherwise.
+    pub fn sign(&self) -> i8 {
+        match (self.len() - self.cycles().len()) % 2 {
+            0 => 1,
+            _ => -1,
+        }
+    }
+}
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn parse_cycles(s: &str) -> Result<Vec<Vec<usize>>, DeckError> {
+    let s = s.trim();
+    if !s.starts_with('(') || !s.ends_with(')') {
+        return Err(DeckError::InvalidPermutation);
+    }
+    s[1..s.len() - 1]
+        .split(')')
+        .map(|cycle| {
+            cycle
+                .trim()
+                .strip_prefix('(')
+                .unwrap_or(cycle)
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|n| !n.is_empty())
+                .map(|n| n.parse().map_err(|_| DeckError::InvalidPermutation))
+                .collect()
+        })
+        .collect()
+}
+
+impl Display for Permutation {
+    /// Cycle notation with fixed points omitted, the identity is written "()".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cycles: Vec<Vec<usize>> = self.cycles().into_iter().filter(|c| c.len() > 1).collect();
+        if cycles.is_empty() {
+            return write!(f, "()");
+        }
+        for cycle in cycles {
+            let cycle: Vec<String> = cycle.iter().map(|i| i.to_string()).collect();
+            write!(f, "({})", cycle.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Permutation {
+    type Err = DeckError;
+
+    /// Parse cycle notation, the number of positions is one more than the largest position that appears. Every
+    /// position must appear so fixed points are written as cycles of one, such as "(0 2)(1)". Use
+    /// from_cycle_notation() to leave fixed points out.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cycles = parse_cycles(s)?;
+        let mut n: usize = 0;
+        let mut count = 0;
+        for &i in cycles.iter().flatten() {
+            n = n.max(i.checked_add(1).ok_or(DeckError::InvalidPermutation)?);
+            count += 1;
+        }
+        // Checking this before allocating keeps the size bounded by the length of the string
+        if count != n {
+            return Err(DeckError::InvalidPermutation);
+        }
+        Permutation::from_cycles(n, &cycles)
+    }
+}
+
+impl TryFrom<Vec<usize>> for Permutation {
+    type Error = DeckError;
+
+    fn try_from(vec: Vec<usize>) -> Result<Self, Self::Error> {
+        Permutation::new(vec)
+    }
+}
+
+impl<T, R: RngCore + SeedableRng> Deck<T, R> {
+    /// Rearrange the cards in place according to the permutation. Returns an error if the permutation is not the
+    /// same length as the deck.
+    pub fn apply(&mut self, perm: &Permutation) -> Result<(), DeckError> {
+        if perm.len() != self.len() {
+            return Err(DeckError::LengthMismatch {
+                expected: self.len(),
+                len: perm.len(),
+            });
+        }
+        // Follow each cycle, swapping the card that belongs in each position into it. A cycle of length k
+        // takes k - 1 swaps and is done in place.
+        let cards = self.cards.make_contiguous();
+        let mut done = vec![false; cards.len()];
+        for start in 0..cards.len() {
+            let mut i = start;
+            while !done[i] {
+                done[i] = true;
+                let j = perm.0[i];
+                if j != start {
+                    cards.swap(i, j);
+                }
+                i = j;
+            }
+        }
+        Ok(())
+    }
+
+    /// Perform an operation on the deck and return the permutation it produced. The operation is run on a deck
+    /// of positions using this deck's RNG so the result is exactly what the operation would have done to this
+    /// deck. The operation must not change the number of cards.
+    pub fn record<F: FnOnce(&mut Deck<usize, R>)>(&mut self, op: F) -> Permutation {
+        let rng = std::mem::replace(&mut self.rng, R::from_seed(R::Seed::default()));
+        let mut positions = Deck::with_rng((0..self.len()).collect::<Vec<usize>>(), rng);
+        op(&mut positions);
+        self.rng = positions.rng;
+        let perm = Permutation::new(positions.cards.into())
+            .expect("the operation should only rearrange the cards");
+        self.apply(&perm).unwrap();
+        perm
+    }
+
+    /// Perform a shuffle on a deck of u32 indices using this deck's RNG and then rearrange the cards in place
+    /// with .apply(). The result is the same as performing the shuffle on this deck directly but the cards are
+    /// only swapped at most once per position no matter how many passes the shuffle makes, which is faster when
+    /// the cards are large. The operation must not change the number of cards. Panics if the deck has more than
+    /// u32::MAX cards.
+    pub fn shuffle_indexed<F: FnOnce(&mut Deck<u32, R>)>(&mut self, op: F) {
+        let len = u32::try_from(self.len()).expect("deck should have at most u32::MAX cards");
+        let rng = std::mem::replace(&mut self.rng, R::from_seed(R::Seed::default()));
+        let mut indices = Deck::with_rng((0..len).collect::<Vec<u32>>(), rng);
+        op(&mut indices);
+        self.rng = indices.rng;
+        let perm = Permutation::new(indices.cards.into_iter().map(|i| i as usize).collect())
+            .expect("the operation should only rearrange the cards");
+        self.apply(&perm).unwrap();
+    }
+}
+
+// Record an operation on a seeded deck of n cards a number of times.
+#[cfg(test)]
+pub(crate) fn observe<F: FnMut(&mut Deck<usize>)>(
+    n: usize,
+    trials: usize,
+    mut op: F,
+) -> Vec<Permutation> {
+    let mut deck = Deck::from_iter(0..n);
+    deck.set_seed_u64(314159);
+    (0..trials).map(|_| deck.record(&mut op)).collect()
+}
+
+#[cfg(test)]
+mod test_permutation {
+    use super::*;
+
+    #[test]
+    fn validation() {
+        assert!(Permutation::new(vec![2, 0, 1]).is_ok());
+        assert_eq!(
+            Permutation::new(vec![0, 0, 1]),
+            Err(DeckError::InvalidPermutation)
+        );
+        assert!(Permutation::new(vec![0, 3, 1]).is_err());
+        assert!(Permutation::from_cycles(3, &[[0, 1], [1, 2]]).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_validates() {
+        let p: Permutation = serde_json::from_str("[2,0,1]").unwrap();
+        assert_eq!(p.as_slice(), [2, 0, 1]);
+        assert_eq!(serde_json::to_string(&p).unwrap(), "[2,0,1]");
+        assert!(serde_json::from_str::<Permutation>("[0,0,5]").is_err());
+    }
+
+    #[test]
+    fn matches_cycle() {
+        let mut a = Deck::from_iter(0..5);
+        let mut b = Deck::from_iter(0..5);
+        a.cycle(&[0, 3, 1]).unwrap();
+        b.apply(&Permutation::from_cycles(5, &[[0, 3, 1]]).unwrap())
+            .unwrap();
+        assert_eq!(a.cards, b.cards);
+    }
+
+    #[test]
+    fn large_order() {
+        // Cycles of every prime below 400 have an order far beyond u128
+        let primes: Vec<usize> = (2..400).filter(|&n| (2..n).all(|d| n % d != 0)).collect();
+        let mut start = 0;
+        let cycles: Vec<Vec<usize>> = primes
+            .iter()
+            .map(|&p| {
+                start += p;
+                (start - p..start).collect()
+            })
+            .collect();
+        let perm = Permutation::from_cycles(start, &cycles).unwrap();
+        assert_eq!(perm.len(), 13_887);
+        let product = primes.iter().fold(BigUint::one(), |acc, &p| acc * p);
+        assert_eq!(perm.order(), product);
+    }
+
+    #[test]
+    fn algebra() {
+        let p: Permutation = "(0 1 2)(3 4)".parse().unwrap();
+        assert_eq!(p.len(), 5);
+        assert_eq!(p.order(), BigUint::from(6u32));
+        assert_eq!(p.sign(), -1);
+        assert!(p.pow(6).is_identity());
+        assert_eq!(p.pow(-1), p.inverse());
+        assert!(p.then(&p.inverse()).unwrap().is_identity());
+        assert_eq!(p.pow(2), p.then(&p).unwrap());
+        assert_eq!(p.to_string(), "(0 1 2)(3 4)");
+        assert_eq!(
+            Permutation::from_cycle_notation("(1, 3)", 5)
+                .unwrap()
+                .cycles(),
+            [vec![0], vec![1, 3], vec![2], vec![4]]
+        );
+        assert_eq!(Permutation::identity(4).to_string(), "()");
+        assert_eq!("(0 2)(1)".parse(), Ok(Permutation(vec![2, 1, 0])));
+        assert_eq!("()".parse(), Ok(Permutation::identity(0)));
+        assert!("(1 3)".parse::<Permutation>().is_err());
+        assert!("(100000000000)".parse::<Permutation>().is_err());
+        assert!("(18446744073709551615)".parse::<Permutation>().is_err());
+
+        let q = Permutation::new(vec![2, 0, 3, 4, 1]).unwrap();
+        assert_eq!(q.descents(), 2);
+        assert_eq!(q.inversions(), 4);
+        assert_eq!(q.fixed_points(), 0);
+        assert_eq!(q.longest_increasing_subsequence(), 3);
+        assert_eq!(q.cycle_type(), [5]);
+        assert_eq!(p.cycle_type(), [3, 2]);
+    }
+
+    #[test]
+    fn record() {
+        let mut deck = Deck::from_iter(10..20);
+        deck.set_seed_u64(314159);
+        let perm = deck.record(|d| d.riffle());
+
+        // The riffle test in shuffling uses the same seed
+        assert_eq!(perm.as_slice(), [0, 7, 1, 2, 3, 8, 4, 5, 9, 6]);
+        assert_eq!(deck.cards, [10, 17, 11, 12, 13, 18, 14, 15, 19, 16]);
+        assert_eq!(perm.rising_sequences(), 2);
+        assert_eq!(Permutation::identity(5).rising_sequences(), 1);
+
+        let mut replay = Deck::from_iter(10..20);
+        replay.apply(&perm).unwrap();
+        assert_eq!(replay.cards, deck.cards);
+    }
+
+    #[test]
+    fn shuffle_indexed() {
+        // A large payload that is not Clone
+        #[derive(Debug, PartialEq)]
+        struct Big(usize, [u64; 64]);
+
+        for op in [
+            |d: &mut Deck<u32>| d.riffle(),
+            |d: &mut Deck<u32>| d.overhand(0.3),
+            |d: &mut Deck<u32>| d.pile_shuffle(5),
+            |d: &mut Deck<u32>| d.faro(true),
+        ] {
+            let mut big = Deck::from_iter((0..52).map(|i| Big(i, [0; 64])));
+            big.set_seed_u64(314159);
+            let mut small = Deck::from_iter(0..52_u32);
+            small.set_seed_u64(314159);
+            for _ in 0..3 {
+                big.shuffle_indexed(op);
+                op(&mut small);
+            }
+            let ids: Vec<u32> = big.iter().map(|b| b.0 as u32).collect();
+            assert_eq!(small.cards, ids);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{
+    analysis::PositionMatrix,
+    error::DeckError,
+    permutation::Permutation,
+    special::{chi_square_sf, normal_sf},
+};
+
+/// The outcome of a single hypothesis test. The null hypothesis is always that the decks were uniformly shuffled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    /// Name of the test.
+    pub name: &'static str,
+    /// The test statistic, a z-score or a chi-square value depending on the test.
+    pub statistic: f64,
+    /// Probability of a statistic at least this extreme if the decks were uniformly shuffled.
+    pub p_value: f64,
+}
+
+/// The results of every test in the suite.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomnessReport {
+    /// Significance level for the suite as a whole.
+    pub alpha: f64,
+    pub tests: Vec<TestResult>,
+}
+
+impl RandomnessReport {
+    /// True if no test rejects uniformity. Each p-value is compared against alpha divided by the number of
+    /// tests so the chance of failing a truly uniform shuffle is at most alpha.
+    pub fn passed(&self) -> bool {
+        let level = self.alpha / self.tests.len() as f64;
+        self.tests.iter().all(|t| t.p_value >= level)
+    }
+
+    /// The tests that reject uniformity at the level used by passed().
+    pub fn failures(&self) -> Vec<&TestResult> {
+        let level = self.alpha / self.tests.len() as f64;
+        self.tests.iter().filter(|t| t.p_value < level).collect()
+    }
+}
+
+/// Run every test on the observed shuffles. The chi-square tests need several times as many observations as
+/// there are cards. Returns an error for invalid observations, fewer than two cards, or an invalid alpha.
+pub fn randomness_tests(
+    observed: &[Permutation],
+    alpha: f64,
+) -> Result<RandomnessReport, DeckError> {
+    if !(0.0..=1.0).contains(&alpha) {
+        return Err(DeckError::InvalidProbability(alpha));
+    }
+    let matrix = PositionMatrix::from_permutations(observed)?;
+    if observed.is_empty() {
+        return Err(DeckError::NoObservations);
+    }
+    let n = matrix.n();
+    if n < 2 {
+        return Err(DeckError::NotEnoughCards { needed: 2, len: n });
+    }
+    Ok(RandomnessReport {
+        alpha,
+        tests: vec![
+            adjacent_pairs(observed),
+            rising_sequences(observed),
+            position_chi_square(&matrix),
+            top_card(&matrix),
+        ],
+    })
+}
+
+/// Count how often a card is still directly above the card that was below it to begin with. A poor shuffle
+/// leaves too many of these pairs intact. Uses a normal approximation with the exact mean and variance of
+/// the count for a uniform permutation.
+pub fn adjacent_pairs(observed: &[Permutation]) -> TestResult {
+    let n = observed.first().map(|p| p.len()).unwrap_or(0) as f64;
+    let count: usize = observed
+        .iter()
+        .map(|p| p.as_slice().windows(2).filter(|w| w[1] == w[0] + 1).count())
+        .sum();
+    let m = observed.len() as f64;
+    let mean = (n - 1.0) / n;
+    let var = (n - 1.0) / n - (n - 1.0) / (n * n) + (n - 2.0) / n - (n - 1.0) * (n - 2.0) / (n * n);
+    let z = (count as f64 - m * mean) / (m * var).sqrt();
+    TestResult {
+        name: "adjacent pairs",
+        statistic: z,
+        p_value: normal_sf(z),
+    }
+}
+
+/// Count the rising sequences of each permutation. A few riffles leave too few of them. Uses a normal
+/// approximation with the mean (n + 1) / 2 and variance (n + 1) / 12 for a uniform permutation.
+pub fn rising_sequences(observed: &[Permutation]) -> TestResult {
+    let n = observed.first().map(|p| p.len()).unwrap_or(0) as f64;
+    let total: usize = observed.iter().map(|p| p.rising_sequences()).sum();
+    let m = observed.len() as f64;
+    let z = (total as f64 - m * (n + 1.0) / 2.0) / (m * (n + 1.0) / 12.0).sqrt();
+    TestResult {
+        name: "rising sequences",
+        statistic: z,
+        p_value: normal_sf(-z),
+    }
+}
+
+/// Pearson's chi-square test that every card is equally likely to end at every position.
+pub fn position_chi_square(matrix: &PositionMatrix) -> TestResult {
+    let stat = matrix.chi_square();
+    TestResult {
+        name: "position chi-square",
+        statistic: stat,
+        p_value: chi_square_sf(stat, matrix.degrees_of_freedom() as f64),
+    }
+}
+
+/// Pearson's chi-square test that every card is equally likely to end on top.
+pub fn top_card(matrix: &PositionMatrix) -> TestResult {
+    let n = matrix.n();
+    let expected = matrix.trials() as f64 / n as f64;
+    let stat = (0..n)
+        .map(|card| (matrix.count(card, 0) as f64 - expected).powi(2) / expected)
+        .sum();
+    TestResult {
+        name: "top card",
+        statistic: stat,
+        p_value: chi_square_sf(stat, (n - 1) as f64),
+    }
+}
+
+#[cfg(test)]
+mod test_randomness {
+    use super::*;
+    use crate::permutation::observe;
+
+    #[test]
+    fn uniform_passes() {
+        let report = randomness_tests(&observe(20, 2000, |d| d.shuffle()), 0.01).unwrap();
+        assert!(report.passed(), "{:?}", report);
+        assert_eq!(report.tests.len(), 4);
+    }
+
+    #[test]
+    fn poor_shuffles_fail() {
+        let report = randomness_tests(&observe(20, 2000, |d| d.riffle()), 0.01).unwrap();
+        assert!(!report.passed());
+        assert!(report
+            .failures()
+            .iter()
+            .any(|t| t.name == "rising sequences"));
+
+        let report = randomness_tests(&observe(20, 2000, |d| d.overhand(0.3)), 0.01).unwrap();
+        assert!(!report.passed());
+        assert!(report.failures().iter().any(|t| t.name == "adjacent pairs"));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(randomness_tests(&[], 0.05), Err(DeckError::NoObservations));
+        assert_eq!(
+            randomness_tests(&[Permutation::identity(1)], 0.05),
+            Err(DeckError::NotEnoughCards { needed: 2, len: 1 })
+        );
+        assert_eq!(
+            randomness_tests(&[Permutation::identity(3)], 1.5),
+            Err(DeckError::InvalidProbability(1.5))
+        );
+    }
+}
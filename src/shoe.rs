@@ -0,0 +1,203 @@
+use rand::{RngCore, SeedableRng};
+use rand_distr::{Binomial, Distribution};
+use rand_xoshiro::Xoshiro256PlusPlus;
+use std::collections::VecDeque;
+
+use crate::{deck::Deck, error::DeckError};
+
+/// Where the cut card is placed in a Shoe each time it is shuffled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CutCard {
+    /// The cut card is placed exactly this many cards from the top.
+    Exact(usize),
+    /// The cut card is placed following a binomial distribution over the cards in the shoe with the given
+    /// probability, so 0.75 places it roughly three quarters of the way through.
+    Binomial(f64),
+}
+
+/// A casino shoe made of several decks with a cut card and a discard tray. When the cut card is reached the
+/// discards are gathered, the shoe is reshuffled, and the cut card is placed again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shoe<T, R: RngCore + SeedableRng = Xoshiro256PlusPlus> {
+    deck: Deck<T, R>,
+    discards: VecDeque<T>,
+    cut_card: CutCard,
+    cut_at: usize,
+    dealt: usize,
+}
+
+impl<T> Shoe<T> {
+    /// Combine several decks into a shoe then shuffle it and place the cut card. The shoe uses the RNG of the
+    /// first deck.
+    pub fn new(decks: Vec<Deck<T>>, cut_card: CutCard) -> Result<Shoe<T>, DeckError> {
+        Shoe::from_deck(Deck::from(decks), cut_card)
+    }
+}
+
+impl<T, R: RngCore + SeedableRng> Shoe<T, R> {
+    /// Use an already assembled deck as the shoe then shuffle it and place the cut card. All randomness comes
+    /// from the RNG of the deck so a seeded deck gives a reproducible shoe.
+    pub fn from_deck(deck: Deck<T, R>, cut_card: CutCard) -> Result<Shoe<T, R>, DeckError> {
+        match cut_card {
+            CutCard::Exact(n) => deck.check_position(n)?,
+            CutCard::Binomial(p) => {
+                if !(0.0..=1.0).contains(&p) {
+                    return Err(DeckError::InvalidProbability(p));
+                }
+            }
+        }
+        let mut shoe = Shoe {
+            deck,
+            discards: VecDeque::new(),
+            cut_card,
+            cut_at: 0,
+            dealt: 0,
+        };
+        shoe.reshuffle();
+        Ok(shoe)
+    }
+
+    // Choose the number of cards that will be dealt before the cut card comes out.
+    fn place_cut_card(&mut self) {
+        self.dealt = 0;
+        self.cut_at = match self.cut_card {
+            CutCard::Exact(n) => n.min(self.deck.len()),
+            CutCard::Binomial(p) => {
+                let bin = Binomial::new(self.deck.len().try_into().unwrap(), p).unwrap();
+                usize::try_from(bin.sample(&mut self.deck.rng)).unwrap()
+            }
+        };
+    }
+
+    /// Draw the next card from the shoe.
+    pub fn draw(&mut self) -> Option<T> {
+        let card = self.deck.draw()?;
+        self.dealt += 1;
+        Some(card)
+    }
+
+    /// True once as many cards have been dealt as were in front of the cut card.
+    pub fn cut_card_reached(&self) -> bool {
+        self.dealt >= self.cut_at
+    }
+
+    /// Number of cards that can be dealt before the cut card is reached.
+    pub fn cards_until_cut(&self) -> usize {
+        self.cut_at.saturating_sub(self.dealt)
+    }
+
+    /// Number of cards remaining in the shoe.
+    pub fn len(&self) -> usize {
+        self.deck.len()
+    }
+
+    /// True if there are no cards remaining in the shoe.
+    pub fn is_empty(&self) -> bool {
+        self.deck.is_empty()
+    }
+
+    /// Place a card in the discard tray.
+    pub fn discard(&mut self, card: T) {
+        self.discards.push_back(card)
+    }
+
+    /// Place several cards in the discard tray.
+    pub fn discard_all<I: IntoIterator<Item = T>>(&mut self, cards: I) {
+        self.discards.extend(cards)
+    }
+
+    /// Number of cards in the discard tray.
+    pub fn discards_len(&self) -> usize {
+        self.discards.len()
+    }
+
+    /// A reference to the cards remaining in the shoe.
+    pub fn deck(&self) -> &Deck<T, R> {
+        &self.deck
+    }
+
+    /// Gather the discards into the shoe, perform a Fisher-Yates shuffle, and place the cut card.
+    pub fn reshuffle(&mut self) {
+        self.reshuffle_with(|deck| deck.shuffle())
+    }
+
+    /// Gather the discards into the shoe, shuffle with the provided function, and place the cut card.
+    pub fn reshuffle_with<F: FnOnce(&mut Deck<T, R>)>(&mut self, shuffle: F) {
+        self.deck.cards.append(&mut self.discards);
+        shuffle(&mut self.deck);
+        self.place_cut_card();
+    }
+
+    /// Reshuffle only if the cut card has been reached. Returns true if a reshuffle happened.
+    pub fn reshuffle_if_needed(&mut self) -> bool {
+        if self.cut_card_reached() {
+            self.reshuffle();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_shoe {
+    use super::*;
+
+    fn seeded_deck() -> Deck<i32> {
+        let mut deck = Deck::from((0..6).map(|_| Deck::from_iter(0..52)).collect::<Vec<_>>());
+        deck.set_seed_u64(314159);
+        deck
+    }
+
+    #[test]
+    fn exact_cut_card() {
+        let mut shoe = Shoe::from_deck(seeded_deck(), CutCard::Exact(234)).unwrap();
+        assert_eq!(shoe.len(), 312);
+        for _ in 0..233 {
+            let card = shoe.draw().unwrap();
+            shoe.discard(card);
+        }
+        assert!(!shoe.cut_card_reached());
+        let card = shoe.draw().unwrap();
+        shoe.discard(card);
+        assert!(shoe.cut_card_reached());
+        assert_eq!(shoe.discards_len(), 234);
+
+        assert!(shoe.reshuffle_if_needed());
+        assert_eq!(shoe.len(), 312);
+        assert_eq!(shoe.discards_len(), 0);
+        assert_eq!(shoe.cards_until_cut(), 234);
+    }
+
+    #[test]
+    fn binomial_cut_card() {
+        let a = Shoe::from_deck(seeded_deck(), CutCard::Binomial(0.75)).unwrap();
+        let b = Shoe::from_deck(seeded_deck(), CutCard::Binomial(0.75)).unwrap();
+        assert_eq!(a, b);
+        assert!(a.cards_until_cut() > 156 && a.cards_until_cut() < 312);
+    }
+
+    #[test]
+    fn new_uses_first_deck_rng() {
+        let decks = || {
+            let mut first = Deck::from_iter(0..52);
+            first.set_seed_u64(314159);
+            vec![first, Deck::from_iter(0..52)]
+        };
+        let a = Shoe::new(decks(), CutCard::Binomial(0.75)).unwrap();
+        let b = Shoe::new(decks(), CutCard::Binomial(0.75)).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn invalid_cut_card() {
+        assert_eq!(
+            Shoe::from_deck(seeded_deck(), CutCard::Exact(313)),
+            Err(DeckError::IndexOutOfBounds {
+                index: 313,
+                len: 312
+            })
+        );
+        assert!(Shoe::from_deck(seeded_deck(), CutCard::Binomial(1.1)).is_err());
+    }
+}
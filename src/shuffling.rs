@@ -1,102 +1,170 @@
-use rand::{seq::SliceRandom, Rng};
+use rand::{seq::SliceRandom, Rng, RngCore, SeedableRng};
 
-use crate::deck::Deck;
+use crate::{deck::Deck, error::DeckError, permutation::Permutation};
+use std::{
+    collections::VecDeque,
+    ops::{Bound, RangeBounds},
+};
 
-impl<T> Deck<T> {
+impl<T, R: RngCore + SeedableRng> Deck<T, R> {
     /// Perform a Fisher-Yates shuffle on the deck. This is a mathematically correct shuffle that gives every card
     /// an equal chance of ending up at any postion. It should be perferred whenever thorough shuffling is needed. Also
     /// the fastest method provided.
     pub fn shuffle(&mut self) {
-        let mut rng = rand::thread_rng();
-        self.cards.make_contiguous().shuffle(&mut rng);
+        self.cards.make_contiguous().shuffle(&mut self.rng);
     }
 
     /// Extends the Deck with another and then shuffles the result.
-    pub fn shuffle_with(&mut self, right: Deck<T>) {
+    pub fn shuffle_with(&mut self, right: Deck<T, R>) {
         self.extend(right);
         self.shuffle();
     }
 
+    /// Perform a Fisher-Yates shuffle on only the cards within the range of positions, leaving the rest in
+    /// place. Returns an error if the range extends past the end of the deck or starts after it ends.
+    pub fn shuffle_range<B: RangeBounds<usize>>(&mut self, range: B) -> Result<(), DeckError> {
+        let past_end = DeckError::IndexOutOfBounds {
+            index: usize::MAX,
+            len: self.len(),
+        };
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.checked_add(1).ok_or(past_end)?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.checked_add(1).ok_or(past_end)?,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => self.len(),
+        };
+        if start > end {
+            return Err(DeckError::InvalidRange { start, end });
+        }
+        self.check_position(end)?;
+        self.cards.make_contiguous()[start..end].shuffle(&mut self.rng);
+        Ok(())
+    }
+
+    /// Bring k uniformly random cards, in a uniformly random order, to the top of the deck. This is a Fisher-Yates
+    /// shuffle that stops after k steps so it runs in O(k). The order of the remaining cards is not random. If k
+    /// is at least the number of cards the whole deck is shuffled.
+    pub fn shuffle_top(&mut self, k: usize) {
+        let len = self.len();
+        for i in 0..k.min(len) {
+            let j = self.rng.gen_range(i..len);
+            self.cards.swap(i, j);
+        }
+    }
+
+    /// Bring k uniformly random cards, in a uniformly random order, to the bottom of the deck. This is a
+    /// Fisher-Yates shuffle that stops after k steps so it runs in O(k). The order of the remaining cards is not
+    /// random. If k is at least the number of cards the whole deck is shuffled.
+    pub fn shuffle_bottom(&mut self, k: usize) {
+        let len = self.len();
+        for i in (len - k.min(len)..len).rev() {
+            let j = self.rng.gen_range(0..=i);
+            self.cards.swap(i, j);
+        }
+    }
+
     /// Perform a single riffle shuffle of the deck using the Gilbert-Shannon-Reeds algorithm. Poor randomization.
     pub fn riffle(&mut self) {
-        let right = self.split_off_binom();
+        let n = self.binom();
+        let right = self.split_off_packet(n);
         self.riffle_with(right);
     }
 
     /// Perform a single riffle shuffle of the deck using the Gilbert-Shannon-Reeds algorithm. Poor randomization.
+    /// Panics if n is greater than the number of cards.
     pub fn riffle_at_nth(&mut self, n: usize) {
-        let right = self.split_off_nth(n);
+        let right = self.split_off_packet(n);
         self.riffle_with(right);
     }
 
+    /// Perform a single riffle shuffle of the deck using the Gilbert-Shannon-Reeds algorithm. Poor randomization.
+    /// Returns an error if n is greater than the number of cards.
+    pub fn try_riffle_at_nth(&mut self, n: usize) -> Result<(), DeckError> {
+        self.check_position(n)?;
+        let right = self.split_off_packet(n);
+        self.riffle_with(right);
+        Ok(())
+    }
+
     /// Riffle shuffle another Deck into this one, consuming the other Deck.
-    pub fn riffle_with(&mut self, mut right: Deck<T>) {
-        if self.len() == 0 {
+    pub fn riffle_with(&mut self, right: Deck<T, R>) {
+        if self.is_empty() {
             self.extend(right);
             return;
         }
 
         let mut l_len = self.len();
         let mut r_len = right.len();
-        let mut cursor = 0;
+        let mut right = right.cards;
 
         self.cards.reserve_exact(r_len);
 
-        loop {
-            if r_len == 0 {
-                break;
-            }
+        // Cards are taken from the front of the deck or from the right side and placed on the back of the
+        // deck. The remaining left side cards are at the front so they are moved to the back at the end.
+        while r_len != 0 {
             let l = l_len as f64;
             let r = r_len as f64;
 
-            // If the right branch is chosen, place the card at the cursor position
-            // and reduce the length of the right side
+            // If the right branch is chosen take a card from the right side
             if self.rng.gen_bool(r / (l + r)) {
-                match right.draw_top() {
-                    Some(card) => {
-                        self.place_nth(cursor, card);
-                        r_len -= 1;
-                    }
-                    None => {
-                        self.extend(right);
-                        break;
-                    }
-                }
-            // If the left branch is chosen just reduce the length of the left size.
+                let card = right.pop_front().expect("right side should not be empty");
+                self.cards.push_back(card);
+                r_len -= 1;
+            // If the left branch is chosen take a card from the front of the left side
             } else {
+                let card = self
+                    .cards
+                    .pop_front()
+                    .expect("left side should not be empty");
+                self.cards.push_back(card);
                 l_len -= 1;
             }
-            cursor += 1;
         }
+        self.cards.rotate_left(l_len);
+    }
+
+    /// Perform an a-shuffle as described by Bayer and Diaconis. The deck is cut into a packets following a
+    /// multinomial distribution and then the packets are interleaved, taking each card from a packet with
+    /// probability proportional to the number of cards remaining in it. A 2-shuffle has the same distribution as
+    /// .riffle() and an a-shuffle followed by a b-shuffle has the same distribution as an ab-shuffle, so seven
+    /// riffles can be simulated as a single 128-shuffle. An a of 0 is treated as 1, which does nothing. The time
+    /// taken does not depend on a.
+    pub fn a_shuffle(&mut self, a: usize) {
+        let a = a.max(1);
+
+        // The inverse of an a-shuffle gives each card a random packet and then stacks the packets in order, keeping
+        // the order of the cards within each. Performing that on positions and inverting it gives the a-shuffle.
+        let labels: Vec<usize> = (0..self.len()).map(|_| self.rng.gen_range(0..a)).collect();
+        let mut inverse: Vec<usize> = (0..self.len()).collect();
+        inverse.sort_by_key(|&i| labels[i]);
+        self.apply(&Permutation::new_unchecked(inverse).inverse())
+            .expect("every card should be in a packet");
     }
 
     /// Performs the inverse of a riffle shuffle. This is equivalent to taking cards at random from the deck (in order) to make a new
     /// deck then placing the remains of the original on top. However this is done without creating an additional deck.
     pub fn inverse_riffle(&mut self) {
-        let mut cursor = 0;
-        let mut ctr = 0;
-        loop {
-            if ctr == self.len() {
-                break;
-            }
+        let mut taken = VecDeque::with_capacity(self.len());
+        // Every card is cycled through from front to back and the ones not taken end up in their original order
+        for _ in 0..self.len() {
+            let card = self.cards.pop_front().expect("deck should not be empty");
             if self.rng.gen_bool(0.5) {
-                let card = self
-                    .draw_nth(cursor)
-                    .expect("cursor should not be out of bounds");
-                self.place_bottom(card);
+                taken.push_back(card);
             } else {
-                cursor += 1
+                self.cards.push_back(card);
             }
-            ctr += 1
         }
+        self.cards.append(&mut taken);
     }
 
     /// Perform a Gilbreath shuffle on the deck that uses n cards. Poor randomization.
-    pub fn gilbreath(&mut self, n: usize) -> Result<(), &'static str> {
-        if n > self.len() {
-            return Err("n must be less than the number of cards in the deck");
-        }
-        let new = self.split_off_nth(n);
+    pub fn gilbreath(&mut self, n: usize) -> Result<(), DeckError> {
+        self.check_position(n)?;
+        let new = self.split_off_packet(n);
 
         self.reverse();
         self.riffle_with(new);
@@ -104,7 +172,15 @@ impl<T> Deck<T> {
         Ok(())
     }
 
-    /// Perform an overhand shuffle using Pemantle's algorithm
+    // The shuffles that take a probability pass it to gen_bool which panics if it is not valid.
+    fn check_probability(p: f64) -> Result<(), DeckError> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(DeckError::InvalidProbability(p));
+        }
+        Ok(())
+    }
+
+    /// Perform an overhand shuffle using Pemantle's algorithm. Panics if p is not a valid probability.
     pub fn overhand(&mut self, p: f64) {
         let len = self.len();
         let temp = self.cards.make_contiguous();
@@ -118,8 +194,16 @@ impl<T> Deck<T> {
         self.cards.make_contiguous().reverse()
     }
 
+    /// Perform an overhand shuffle using Pemantle's algorithm. Returns an error if p is not a valid probability.
+    pub fn try_overhand(&mut self, p: f64) -> Result<(), DeckError> {
+        Self::check_probability(p)?;
+        self.overhand(p);
+        Ok(())
+    }
+
     /// Premantle's original algorithm. This has similar statistical properties to an overhand shuffle
-    /// but does not recreate the shuffle itself. Nearly identical running time to overhand.
+    /// but does not recreate the shuffle itself. Nearly identical running time to overhand. Panics if p is not a
+    /// valid probability.
     pub fn premantle(&mut self, p: f64) {
         let len = self.len();
         let temp = self.cards.make_contiguous();
@@ -132,32 +216,99 @@ impl<T> Deck<T> {
         }
     }
 
+    /// Premantle's original algorithm. Returns an error if p is not a valid probability.
+    pub fn try_premantle(&mut self, p: f64) -> Result<(), DeckError> {
+        Self::check_probability(p)?;
+        self.premantle(p);
+        Ok(())
+    }
+
     /// Perform a faro shuffle (a perfect riffle shuffle). An "out shuffle" places the first card
     /// on top. An "in shuffle" places the first card second. This is not a true shuffle as it is
     /// entirely deterministic.
     pub fn faro(&mut self, out: bool) {
         let len = self.len();
+        let left_len = len / 2;
+
+        // The left half is cycled from the front to the back of the deck with the right half woven in
+        let mut right = self.cards.split_off(left_len);
+        for _ in 0..left_len {
+            let left_card = self.cards.pop_front().unwrap();
+            let right_card = right.pop_front().unwrap();
+            if out {
+                self.cards.push_back(left_card);
+                self.cards.push_back(right_card);
+            } else {
+                self.cards.push_back(right_card);
+                self.cards.push_back(left_card);
+            }
+        }
+        self.cards.append(&mut right);
+    }
 
-        let right = self.split_off_nth(len / 2);
+    /// Perform a faro shuffle where the deck is split evenly but each time a card is dropped there is a
+    /// probability of error_p that the next card slips from the same half. Returns the number of mistakes made.
+    /// Returns an error if error_p is not a valid probability.
+    pub fn faro_imperfect(&mut self, out: bool, error_p: f64) -> Result<usize, DeckError> {
+        self.faro_imperfect_at_nth(self.len() / 2, out, error_p)
+    }
 
-        let mut cursor = match out {
-            true => 1,
-            false => 0,
-        };
+    /// Perform a faro shuffle where the deck is split with n cards in the top half. Each time a card is dropped
+    /// there is a probability of error_p that the next card slips from the same half. Once one half runs out the
+    /// rest of the other half is placed on the bottom. Returns the number of mistakes made, with an error_p of
+    /// 0.0 the weave is perfect. Returns an error if n is greater than the number of cards or error_p is not a
+    /// valid probability.
+    pub fn faro_imperfect_at_nth(
+        &mut self,
+        n: usize,
+        out: bool,
+        error_p: f64,
+    ) -> Result<usize, DeckError> {
+        self.check_position(n)?;
+        Self::check_probability(error_p)?;
+
+        let mut bottom = self.cards.split_off(n);
+        let mut top = std::mem::take(&mut self.cards);
+        self.cards.reserve_exact(top.len() + bottom.len());
+        let mut mistakes = 0;
+        let mut from_top = out;
+        while !top.is_empty() && !bottom.is_empty() {
+            let (hand, other) = match from_top {
+                true => (&mut top, &bottom),
+                false => (&mut bottom, &top),
+            };
+            self.cards.push_back(hand.pop_front().unwrap());
+            while !hand.is_empty() && !other.is_empty() && self.rng.gen_bool(error_p) {
+                self.cards.push_back(hand.pop_front().unwrap());
+                mistakes += 1;
+            }
+            from_top = !from_top;
+        }
+        self.cards.append(&mut top);
+        self.cards.append(&mut bottom);
+        Ok(mistakes)
+    }
 
-        for card in right {
-            if cursor >= self.len() {
-                self.place_bottom(card);
-            } else {
-                self.place_nth(cursor, card);
+    // Deal the cards one at a time onto n piles and then stack the piles in the given order, the first pile
+    // in the order ends up on top. Rather than moving the cards into piles the position each card ends up in
+    // is calculated directly. Pile p receives the cards at positions p, p + n, p + 2n, ... and as each card is
+    // placed on top of its pile they come out in reverse.
+    fn stack_piles(&mut self, order: &[usize]) {
+        let n = order.len();
+        let len = self.len();
+        let mut perm = Vec::with_capacity(len);
+        for &pile in order {
+            if pile < len {
+                let count = (len - 1 - pile) / n + 1;
+                perm.extend((0..count).rev().map(|k| pile + k * n));
             }
-            cursor += 2;
         }
+        self.apply(&Permutation::new_unchecked(perm))
+            .expect("every card should be in a pile");
     }
-}
 
-impl<T: Clone> Deck<T> {
-    /// Perform a pile shuffle using n piles. Poorest randomization and slowest to execute.
+    /// Perform a pile shuffle using n piles that are stacked in a random order. Poorest randomization. Panics if
+    /// n is zero.
     pub fn pile_shuffle(&mut self, n: usize) {
         // If n is greather than or equal to the size of the deck
         // it is equivalent to a Fisher-Yates shuffle
@@ -165,20 +316,49 @@ impl<T: Clone> Deck<T> {
             self.shuffle();
             return;
         }
-        let mut decks = vec![Deck::empty(); n];
-        let mut ctr = 0;
-        for _ in 0..self.len() {
-            decks[ctr].place_top(self.draw_top().unwrap());
-            ctr = (ctr + 1) % n;
+        let mut order: Vec<usize> = (0..n).collect();
+        order.shuffle(&mut self.rng);
+        self.stack_piles(&order);
+    }
+
+    /// Perform a pile shuffle using n piles. Returns an error if n is zero.
+    pub fn try_pile_shuffle(&mut self, n: usize) -> Result<(), DeckError> {
+        if n == 0 {
+            return Err(DeckError::ZeroPiles);
+        }
+        self.pile_shuffle(n);
+        Ok(())
+    }
+
+    /// Perform a pile shuffle with one pile for each entry of order, numbered from left to right as they are
+    /// dealt. The piles are stacked in the given order with the first on top. Returns an error if order is
+    /// empty or is not a permutation of the piles.
+    pub fn pile_shuffle_ordered(&mut self, order: &[usize]) -> Result<(), DeckError> {
+        if order.is_empty() {
+            return Err(DeckError::ZeroPiles);
         }
-        decks.shuffle(&mut self.rng);
-        *self = Deck::from(decks);
+        Permutation::new(order.to_vec())?;
+        self.stack_piles(order);
+        Ok(())
+    }
+
+    /// Perform a pile shuffle using n piles and pick them up from left to right with each pile placed on the
+    /// next, so the first pile dealt ends up on top. Returns an error if n is zero.
+    pub fn pile_shuffle_stacked(&mut self, n: usize) -> Result<(), DeckError> {
+        if n == 0 {
+            return Err(DeckError::ZeroPiles);
+        }
+        let order: Vec<usize> = (0..n).collect();
+        self.stack_piles(&order);
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod test_deck {
     use super::*;
+    use crate::{analysis::riffle_probability, permutation::observe};
+    use num_traits::ToPrimitive;
 
     #[test]
     fn riffle() {
@@ -261,4 +441,211 @@ mod test_deck {
         deck.pile_shuffle(3);
         assert_eq!(deck.cards, [9, 6, 3, 0, 7, 4, 1, 8, 5, 2]);
     }
+
+    #[test]
+    fn faro_imperfect() {
+        // With no errors this matches a perfect faro
+        let mut deck = Deck::from_iter(0..9);
+        assert_eq!(deck.faro_imperfect(false, 0.0), Ok(0));
+        assert_eq!(deck.cards, [4, 0, 5, 1, 6, 2, 7, 3, 8]);
+
+        let mut deck = Deck::from_iter(0..10);
+        assert_eq!(deck.faro_imperfect_at_nth(3, true, 0.0), Ok(0));
+        assert_eq!(deck.cards, [0, 3, 1, 4, 2, 5, 6, 7, 8, 9]);
+
+        let mut deck = Deck::from_iter(0..52);
+        deck.set_seed_u64(314159);
+        let mistakes = deck.faro_imperfect(true, 0.2).unwrap();
+        assert!(mistakes > 0);
+        deck.sort();
+        assert_eq!(deck.cards, (0..52).collect::<Vec<_>>());
+
+        assert!(deck.faro_imperfect_at_nth(53, true, 0.0).is_err());
+        assert!(deck.faro_imperfect(true, 1.5).is_err());
+    }
+
+    #[test]
+    fn pile_shuffle_ordered() {
+        let mut deck = Deck::from_iter(0..10);
+        deck.pile_shuffle_ordered(&[2, 0, 1]).unwrap();
+        assert_eq!(deck.cards, [8, 5, 2, 9, 6, 3, 0, 7, 4, 1]);
+
+        let mut deck = Deck::from_iter(0..10);
+        deck.pile_shuffle_stacked(3).unwrap();
+        assert_eq!(deck.cards, [9, 6, 3, 0, 7, 4, 1, 8, 5, 2]);
+
+        // More piles than cards leaves some piles empty
+        let mut deck = Deck::from_iter(0..3);
+        deck.pile_shuffle_ordered(&[4, 3, 2, 1, 0]).unwrap();
+        assert_eq!(deck.cards, [2, 1, 0]);
+
+        assert_eq!(
+            deck.pile_shuffle_ordered(&[0, 0]),
+            Err(DeckError::InvalidPermutation)
+        );
+        assert_eq!(deck.pile_shuffle_stacked(0), Err(DeckError::ZeroPiles));
+    }
+
+    #[test]
+    fn a_shuffle() {
+        // Count the rising sequences of a deck of 0..n
+        fn rising_sequences(deck: &Deck<usize>) -> usize {
+            let mut pos = vec![0; deck.len()];
+            for (i, &card) in deck.iter().enumerate() {
+                pos[card] = i;
+            }
+            1 + pos.windows(2).filter(|w| w[1] < w[0]).count()
+        }
+
+        let mut deck = Deck::from_iter(0..52);
+        deck.set_seed_u64(314159);
+        deck.a_shuffle(1);
+        assert_eq!(deck.cards, (0..52).collect::<Vec<_>>());
+        for a in [2, 3, 8, 128] {
+            let mut deck = Deck::from_iter(0..52);
+            deck.set_seed_u64(314159);
+            deck.a_shuffle(a);
+            assert!(rising_sequences(&deck) <= a);
+            deck.sort();
+            assert_eq!(deck.cards, (0..52).collect::<Vec<_>>());
+        }
+
+        // Huge values of a are as fast as small ones
+        deck.a_shuffle(usize::MAX);
+        deck.sort();
+        assert_eq!(deck.cards, (0..52).collect::<Vec<_>>());
+
+        // A 4-shuffle gives every permutation the probability of two riffles
+        let trials = 100_000;
+        let mut counts = std::collections::HashMap::new();
+        for perm in observe(4, trials, |d| d.a_shuffle(4)) {
+            *counts.entry(perm).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 24);
+        for (perm, &count) in counts.iter() {
+            let expected = riffle_probability(perm, 2).to_f64().unwrap() * trials as f64;
+            assert!(
+                (count as f64 - expected).abs() < 5.0 * expected.sqrt(),
+                "{} {} {}",
+                perm,
+                count,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn partial_shuffles() {
+        let mut deck = Deck::from_iter(0..20);
+        deck.shuffle_range(5..=9).unwrap();
+        assert_eq!(
+            deck.cards.range(..5).copied().collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4]
+        );
+        assert_eq!(
+            deck.cards.range(10..).copied().collect::<Vec<_>>(),
+            (10..20).collect::<Vec<_>>()
+        );
+        deck.sort();
+        assert_eq!(deck.cards, (0..20).collect::<Vec<_>>());
+
+        assert_eq!(
+            deck.shuffle_range(15..21),
+            Err(DeckError::IndexOutOfBounds { index: 21, len: 20 })
+        );
+        assert!(deck.shuffle_range(20..).is_ok());
+        assert_eq!(
+            deck.shuffle_range((Bound::Included(8), Bound::Excluded(3))),
+            Err(DeckError::InvalidRange { start: 8, end: 3 })
+        );
+        assert!(deck.shuffle_range(..=usize::MAX).is_err());
+        assert!(deck
+            .shuffle_range((Bound::Excluded(usize::MAX), Bound::Unbounded))
+            .is_err());
+
+        deck.shuffle_top(25);
+        deck.shuffle_bottom(25);
+        deck.sort();
+        assert_eq!(deck.cards, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn errors() {
+        let mut deck = Deck::from_iter(0..10);
+        assert_eq!(
+            deck.gilbreath(11),
+            Err(DeckError::IndexOutOfBounds { index: 11, len: 10 })
+        );
+        assert!(deck.try_riffle_at_nth(11).is_err());
+        assert_eq!(
+            deck.try_overhand(1.5),
+            Err(DeckError::InvalidProbability(1.5))
+        );
+        assert!(deck.try_premantle(-0.1).is_err());
+        assert_eq!(deck.try_pile_shuffle(0), Err(DeckError::ZeroPiles));
+        assert_eq!(deck.cards, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    // Run the operation twice in a row on two identically seeded decks. Both the result of the first call
+    // and the state of the RNG after it must match for the second call to match.
+    fn assert_deterministic<F: Fn(&mut Deck<i32>)>(op: F) {
+        let mut a = Deck::from_iter(0..52);
+        let mut b = Deck::from_iter(0..52);
+        a.set_seed_u64(314159);
+        b.set_seed_u64(314159);
+        for _ in 0..2 {
+            op(&mut a);
+            op(&mut b);
+            assert_eq!(a.cards, b.cards);
+        }
+    }
+
+    #[test]
+    fn seeded_shuffles() {
+        assert_deterministic(|d| d.shuffle());
+        assert_deterministic(|d| d.shuffle_with(Deck::from_iter(100..110)));
+        assert_deterministic(|d| d.riffle());
+        assert_deterministic(|d| d.riffle_at_nth(20));
+        assert_deterministic(|d| d.riffle_with(Deck::from_iter(100..110)));
+        assert_deterministic(|d| d.inverse_riffle());
+        assert_deterministic(|d| d.gilbreath(20).unwrap());
+        assert_deterministic(|d| d.overhand(0.3));
+        assert_deterministic(|d| d.premantle(0.3));
+        assert_deterministic(|d| d.pile_shuffle(5));
+        assert_deterministic(|d| d.pile_shuffle(100));
+        assert_deterministic(|d| d.shuffle_range(10..20).unwrap());
+        assert_deterministic(|d| d.shuffle_top(5));
+        assert_deterministic(|d| d.shuffle_bottom(5));
+        assert_deterministic(|d| d.a_shuffle(128));
+        assert_deterministic(|d| {
+            d.faro_imperfect(true, 0.1).unwrap();
+        });
+    }
+
+    #[test]
+    fn seeded_deck_methods() {
+        assert_deterministic(|d| {
+            let card = d.draw_random().unwrap();
+            d.place_random(card)
+        });
+        assert_deterministic(|d| {
+            let card = d.draw_binom().unwrap();
+            d.place_binom(card)
+        });
+        assert_deterministic(|d| d.cut_random());
+        assert_deterministic(|d| d.cut_binom());
+        assert_deterministic(|d| {
+            let bottom = d.split_off_random();
+            d.extend(bottom)
+        });
+        assert_deterministic(|d| {
+            let bottom = d.split_off_binom();
+            d.place_top(bottom.len() as i32)
+        });
+        assert_deterministic(|d| {
+            let (top, bottom) = std::mem::replace(d, Deck::empty()).split_random();
+            *d = top;
+            d.place_top(bottom.len() as i32);
+        });
+    }
 }
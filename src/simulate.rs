@@ -0,0 +1,71 @@
+use rayon::prelude::*;
+
+use crate::deck::Deck;
+
+// Number of consecutive trials that share an RNG stream. Streams are assigned by block rather than by thread so
+// the results do not depend on how many threads are used.
+const SIM_BLOCK: usize = 64;
+
+/// Run a Monte Carlo simulation in parallel. Every trial gets a fresh clone of the deck and the results are
+/// returned in trial order. Each block of trials uses a clone of the deck whose RNG has been jumped a distinct
+/// number of times, so the streams never overlap and a seeded deck gives the same results for any number of
+/// threads. If threads is 0 rayon chooses the number of threads.
+pub fn simulate<T, F, O>(deck: &Deck<T>, trials: usize, threads: usize, op: F) -> Vec<O>
+where
+    T: Clone + Send + Sync,
+    F: Fn(&mut Deck<T>) -> O + Sync,
+    O: Send,
+{
+    let blocks = trials.div_ceil(SIM_BLOCK);
+    let mut starts = Vec::with_capacity(blocks);
+    let mut start = deck.clone();
+    for _ in 0..blocks {
+        start.jump();
+        starts.push(start.clone());
+    }
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("thread pool should build");
+
+    pool.install(|| {
+        starts
+            .into_par_iter()
+            .enumerate()
+            .flat_map_iter(|(b, mut stream)| {
+                let n = SIM_BLOCK.min(trials - b * SIM_BLOCK);
+                let mut out = Vec::with_capacity(n);
+                for _ in 0..n {
+                    let mut trial = stream.clone();
+                    out.push(op(&mut trial));
+                    // The next trial continues the stream where this one left off
+                    stream.rng = trial.rng;
+                }
+                out
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod test_simulate {
+    use super::*;
+
+    #[test]
+    fn reproducible() {
+        let mut deck = Deck::from_iter(0..52);
+        deck.set_seed_u64(314159);
+        let op = |d: &mut Deck<i32>| {
+            d.riffle();
+            d.riffle();
+            *d.top().unwrap()
+        };
+        let one = simulate(&deck, 1000, 1, op);
+        let four = simulate(&deck, 1000, 4, op);
+        assert_eq!(one.len(), 1000);
+        assert_eq!(one, four);
+        // Trials do not all see the same randomness
+        assert!(one.iter().any(|&t| t != one[0]));
+    }
+}
@@ -0,0 +1,125 @@
+// Special functions needed for p-values and confidence intervals. These follow the standard methods from
+// Numerical Recipes and are accurate to around 1e-10, which is plenty for hypothesis testing.
+
+const LANCZOS: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7,
+];
+
+// Natural log of the gamma function for x > 0.
+pub(crate) fn ln_gamma(x: f64) -> f64 {
+    if x < 0.5 {
+        // Reflection formula
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum = LANCZOS[1..]
+        .iter()
+        .enumerate()
+        .fold(LANCZOS[0], |acc, (i, c)| acc + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+// The regularized upper incomplete gamma function Q(a, x).
+pub(crate) fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let ln_front = a * x.ln() - x - ln_gamma(a);
+    if x < a + 1.0 {
+        // Series for the lower function P(a, x)
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut ap = a;
+        for _ in 0..1000 {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        1.0 - sum * ln_front.exp()
+    } else {
+        // Lentz's method for the continued fraction
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        h * ln_front.exp()
+    }
+}
+
+// Probability that a chi-square variable with k degrees of freedom is at least x.
+pub(crate) fn chi_square_sf(x: f64, k: f64) -> f64 {
+    gamma_q(k / 2.0, x / 2.0)
+}
+
+// Probability that a standard normal variable is at least z.
+pub(crate) fn normal_sf(z: f64) -> f64 {
+    // erfc(x) = Q(1/2, x^2) for x >= 0
+    let erfc = gamma_q(0.5, z * z / 2.0);
+    match z >= 0.0 {
+        true => erfc / 2.0,
+        false => 1.0 - erfc / 2.0,
+    }
+}
+
+// The z such that a standard normal variable is at least z with probability p, found by bisection.
+pub(crate) fn normal_isf(p: f64) -> f64 {
+    let (mut lo, mut hi) = (-40.0, 40.0);
+    for _ in 0..200 {
+        let mid = (lo + hi) / 2.0;
+        match normal_sf(mid) > p {
+            true => lo = mid,
+            false => hi = mid,
+        }
+    }
+    (lo + hi) / 2.0
+}
+
+#[cfg(test)]
+mod test_special {
+    use super::*;
+
+    #[test]
+    fn known_values() {
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-10);
+        assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-10);
+        assert!((normal_sf(1.959964) - 0.025).abs() < 1e-6);
+        assert!((normal_sf(-1.0) - 0.841345).abs() < 1e-6);
+        assert!((normal_isf(0.025) - 1.959964).abs() < 1e-5);
+        // Critical values of the chi-square distribution at the 5% level
+        assert!((chi_square_sf(3.841459, 1.0) - 0.05).abs() < 1e-6);
+        assert!((chi_square_sf(18.307038, 10.0) - 0.05).abs() < 1e-6);
+        assert!((chi_square_sf(124.342113, 100.0) - 0.05).abs() < 1e-6);
+    }
+}
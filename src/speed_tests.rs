@@ -1,9 +1,11 @@
 #[cfg(test)]
 mod test_deck {
-    use crate::deck::Deck;
+    use crate::{batch::DeckBatch, deck::Deck};
 
     const NUM_CARDS: usize = 52;
     const NUM_TRIALS: usize = 50_000;
+    // Large cards are much slower to move so fewer trials are used for them
+    const LARGE_TRIALS: usize = 5_000;
 
     use std::{fs::File, io::Write};
 
@@ -26,7 +28,7 @@ mod test_deck {
         let time = std::time::SystemTime::now();
         for _ in 0..NUM_TRIALS {
             deck.shuffle();
-            firsts.push(deck.top().unwrap().clone())
+            firsts.push(*deck.top().unwrap())
         }
         let elapsed = time.elapsed().unwrap();
         writeln!(f, "Fisher-Yates: {:?}", elapsed).unwrap();
@@ -36,7 +38,7 @@ mod test_deck {
         let time = std::time::SystemTime::now();
         for _ in 0..NUM_TRIALS {
             deck.riffle();
-            firsts.push(deck.top().unwrap().clone())
+            firsts.push(*deck.top().unwrap())
         }
         let elapsed = time.elapsed().unwrap();
         writeln!(f, "Riffle: {:?}", elapsed).unwrap();
@@ -46,7 +48,7 @@ mod test_deck {
         let time = std::time::SystemTime::now();
         for _ in 0..NUM_TRIALS {
             deck.overhand(0.3);
-            firsts.push(deck.top().unwrap().clone())
+            firsts.push(*deck.top().unwrap())
         }
         let elapsed = time.elapsed().unwrap();
         writeln!(f, "Overhand: {:?}", elapsed).unwrap();
@@ -56,7 +58,7 @@ mod test_deck {
         let time = std::time::SystemTime::now();
         for _ in 0..NUM_TRIALS {
             deck.premantle(0.3);
-            firsts.push(deck.top().unwrap().clone())
+            firsts.push(*deck.top().unwrap())
         }
         let elapsed = time.elapsed().unwrap();
         writeln!(f, "Premantle: {:?}", elapsed).unwrap();
@@ -66,7 +68,7 @@ mod test_deck {
         let time = std::time::SystemTime::now();
         for _ in 0..NUM_TRIALS {
             deck.pile_shuffle(5);
-            firsts.push(deck.top().unwrap().clone())
+            firsts.push(*deck.top().unwrap())
         }
         let elapsed = time.elapsed().unwrap();
         writeln!(f, "5 Piles: {:?}", elapsed).unwrap();
@@ -76,10 +78,75 @@ mod test_deck {
         let time = std::time::SystemTime::now();
         for _ in 0..NUM_TRIALS {
             deck.pile_shuffle(10);
-            firsts.push(deck.top().unwrap().clone())
+            firsts.push(*deck.top().unwrap())
         }
         let elapsed = time.elapsed().unwrap();
         writeln!(f, "10 Piles: {:?}", elapsed).unwrap();
         writeln!(o, "{:?}", firsts).unwrap();
+
+        // Many decks shuffled once each, as separate decks and as a single batch
+        writeln!(
+            f,
+            "Shuffle {} decks of {} cards once each, separately and as a batch.",
+            NUM_TRIALS, NUM_CARDS
+        )
+        .unwrap();
+        let mut decks: Vec<Deck<usize>> = (0..NUM_TRIALS)
+            .map(|_| Deck::from_iter(0..NUM_CARDS))
+            .collect();
+
+        firsts.clear();
+        let time = std::time::SystemTime::now();
+        for deck in decks.iter_mut() {
+            deck.shuffle();
+            firsts.push(*deck.top().unwrap())
+        }
+        let elapsed = time.elapsed().unwrap();
+        writeln!(f, "Decks: {:?}", elapsed).unwrap();
+        writeln!(o, "{:?}", firsts).unwrap();
+
+        let mut batch = DeckBatch::repeat(&mut deck, NUM_TRIALS);
+        firsts.clear();
+        let time = std::time::SystemTime::now();
+        batch.shuffle_all();
+        firsts.extend(batch.iter().map(|d| d[0]));
+        let elapsed = time.elapsed().unwrap();
+        writeln!(f, "Batch: {:?}", elapsed).unwrap();
+        writeln!(o, "{:?}", firsts).unwrap();
+
+        // Cards of 512 bytes each, riffled seven times directly and through a deck of indices
+        writeln!(
+            f,
+            "Riffle seven times a deck of {} 512 byte cards {} times.",
+            NUM_CARDS, LARGE_TRIALS
+        )
+        .unwrap();
+        let mut large = Deck::from_iter((0..NUM_CARDS as u64).map(|i| [i; 64]));
+
+        firsts.clear();
+        let time = std::time::SystemTime::now();
+        for _ in 0..LARGE_TRIALS {
+            for _ in 0..7 {
+                large.riffle();
+            }
+            firsts.push(large.top().unwrap()[0] as usize)
+        }
+        let elapsed = time.elapsed().unwrap();
+        writeln!(f, "Direct: {:?}", elapsed).unwrap();
+        writeln!(o, "{:?}", firsts).unwrap();
+
+        firsts.clear();
+        let time = std::time::SystemTime::now();
+        for _ in 0..LARGE_TRIALS {
+            large.shuffle_indexed(|d| {
+                for _ in 0..7 {
+                    d.riffle();
+                }
+            });
+            firsts.push(large.top().unwrap()[0] as usize)
+        }
+        let elapsed = time.elapsed().unwrap();
+        writeln!(f, "Indexed: {:?}", elapsed).unwrap();
+        writeln!(o, "{:?}", firsts).unwrap();
     }
 }
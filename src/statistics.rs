@@ -0,0 +1,171 @@
+use rand::{RngCore, SeedableRng};
+
+use crate::{deck::Deck, error::DeckError, permutation::Permutation};
+
+// Indices of the cards in sorted order, equal cards keep their relative order.
+fn sorted_indices<T: Ord>(cards: impl ExactSizeIterator<Item = T>) -> Vec<usize> {
+    let mut idx: Vec<(T, usize)> = cards.zip(0..).collect();
+    idx.sort();
+    idx.into_iter().map(|(_, i)| i).collect()
+}
+
+impl<T: Ord, R: RngCore + SeedableRng> Deck<T, R> {
+    /// The permutation that takes the cards in sorted order to their current order. Equal cards are treated as
+    /// though the one nearer the top were smaller.
+    pub fn ranks(&self) -> Permutation {
+        let mut ranks = vec![0; self.len()];
+        for (rank, i) in sorted_indices(self.iter()).into_iter().enumerate() {
+            ranks[i] = rank;
+        }
+        Permutation::new_unchecked(ranks)
+    }
+
+    /// The permutation that takes the reference order to the current order of the deck, so that entry i is the
+    /// position in the reference of the card now at position i. Equal cards are matched in the order they
+    /// appear. Returns an error if the reference does not hold the same cards.
+    pub fn relative_to(&self, reference: &[T]) -> Result<Permutation, DeckError> {
+        if reference.len() != self.len() {
+            return Err(DeckError::LengthMismatch {
+                expected: self.len(),
+                len: reference.len(),
+            });
+        }
+        let ours = sorted_indices(self.iter());
+        let theirs = sorted_indices(reference.iter());
+        let mut perm = vec![0; self.len()];
+        for (&i, &j) in ours.iter().zip(theirs.iter()) {
+            if self.cards[i] != reference[j] {
+                return Err(DeckError::InvalidPermutation);
+            }
+            perm[i] = j;
+        }
+        Ok(Permutation::new_unchecked(perm))
+    }
+
+    /// The number of rising sequences, maximal runs of consecutive values that appear in increasing order. A
+    /// sorted deck has one and each riffle shuffle can at most double the number.
+    pub fn rising_sequences(&self) -> usize {
+        self.ranks().rising_sequences()
+    }
+
+    /// The number of positions where a card is greater than the card below it.
+    pub fn descents(&self) -> usize {
+        self.cards
+            .iter()
+            .zip(self.cards.iter().skip(1))
+            .filter(|(a, b)| a > b)
+            .count()
+    }
+
+    /// The number of pairs of cards that are out of order.
+    pub fn inversions(&self) -> usize {
+        self.ranks().inversions()
+    }
+
+    /// The number of cards that are in the position they would have if the deck were sorted.
+    pub fn fixed_points(&self) -> usize {
+        self.ranks().fixed_points()
+    }
+
+    /// The length of the longest sequence of cards, not necessarily adjacent, that is in sorted order.
+    pub fn longest_increasing_subsequence(&self) -> usize {
+        self.ranks().longest_increasing_subsequence()
+    }
+
+    /// The lengths of the cycles of the permutation that sorts the deck, from longest to shortest.
+    pub fn cycle_type(&self) -> Vec<usize> {
+        self.ranks().cycle_type()
+    }
+
+    /// The number of pairs of cards whose order differs from the reference, which is the minimum number of
+    /// swaps of adjacent cards needed to reach it. Returns an error if the reference does not hold the same
+    /// cards.
+    pub fn kendall_tau(&self, reference: &[T]) -> Result<usize, DeckError> {
+        Ok(self.relative_to(reference)?.inversions())
+    }
+
+    /// The total distance each card is from its position in the reference. Returns an error if the reference
+    /// does not hold the same cards.
+    pub fn spearman_footrule(&self, reference: &[T]) -> Result<usize, DeckError> {
+        let perm = self.relative_to(reference)?;
+        Ok(perm
+            .as_slice()
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| i.abs_diff(p))
+            .sum())
+    }
+
+    /// The minimum number of swaps of any two cards needed to reach the reference. Returns an error if the
+    /// reference does not hold the same cards.
+    pub fn cayley_distance(&self, reference: &[T]) -> Result<usize, DeckError> {
+        let perm = self.relative_to(reference)?;
+        Ok(perm.len() - perm.cycles().len())
+    }
+
+    /// The number of positions where the card differs from the reference. Returns an error if the reference
+    /// does not hold the same cards.
+    pub fn hamming_distance(&self, reference: &[T]) -> Result<usize, DeckError> {
+        let perm = self.relative_to(reference)?;
+        Ok(perm.len() - perm.fixed_points())
+    }
+}
+
+#[cfg(test)]
+mod test_statistics {
+    use super::*;
+
+    #[test]
+    fn statistics() {
+        let deck = Deck::from([3, 0, 4, 1, 5, 2]);
+        assert_eq!(deck.rising_sequences(), 2);
+        assert_eq!(deck.descents(), 3);
+        assert_eq!(deck.inversions(), 6);
+        assert_eq!(deck.fixed_points(), 0);
+        assert_eq!(deck.longest_increasing_subsequence(), 3);
+        assert_eq!(deck.cycle_type(), [3, 3]);
+
+        let sorted = Deck::from_iter(0..6);
+        assert_eq!(sorted.rising_sequences(), 1);
+        assert_eq!(sorted.inversions(), 0);
+        assert_eq!(sorted.fixed_points(), 6);
+        assert_eq!(sorted.cycle_type(), [1; 6]);
+    }
+
+    #[test]
+    fn one_riffle() {
+        let mut deck = Deck::from_iter(0..52);
+        deck.set_seed_u64(314159);
+        deck.riffle();
+        assert!(deck.rising_sequences() <= 2);
+        deck.riffle();
+        assert!(deck.rising_sequences() <= 4);
+    }
+
+    #[test]
+    fn distances() {
+        let deck = Deck::from(['b', 'a', 'c', 'd']);
+        let reference = ['d', 'c', 'b', 'a'];
+        assert_eq!(deck.kendall_tau(&reference), Ok(5));
+        assert_eq!(deck.spearman_footrule(&reference), Ok(8));
+        assert_eq!(deck.cayley_distance(&reference), Ok(3));
+        assert_eq!(deck.hamming_distance(&reference), Ok(4));
+        assert_eq!(deck.kendall_tau(&['a', 'b', 'c', 'd']), Ok(1));
+
+        // Equal cards are matched in order
+        let deck = Deck::from([1, 0, 1]);
+        assert_eq!(deck.hamming_distance(&[1, 1, 0]), Ok(2));
+
+        assert_eq!(
+            deck.kendall_tau(&[0, 1]),
+            Err(DeckError::LengthMismatch {
+                expected: 3,
+                len: 2
+            })
+        );
+        assert_eq!(
+            deck.kendall_tau(&[0, 1, 2]),
+            Err(DeckError::InvalidPermutation)
+        );
+    }
+}